@@ -0,0 +1,80 @@
+//! Benchmarks for the hot game loop, runnable on stable with `cargo bench`.
+//!
+//! Pass a name to only run the benchmarks containing it, e.g. `cargo bench -- record`.
+
+use beggar_my_neighbour::{
+    new_deck, safe::SafeGame, Card, Game, Rules, DECK_SIZE, WORLD_RECORD_DECK,
+};
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+/// How long to run each benchmark for, after warming up
+const MEASURE: Duration = Duration::from_secs(3);
+
+/// Runs `f` repeatedly for [`MEASURE`] and prints the average time per call
+fn bench(filter: Option<&str>, name: &str, mut f: impl FnMut()) {
+    if filter.is_some_and(|filter| !name.contains(filter)) {
+        return;
+    }
+
+    // warm up for a tenth of the time, which also sizes the batches so reading the clock stays cheap
+    let warmup = Instant::now();
+    let mut batch = 0u32;
+    while warmup.elapsed() < MEASURE / 10 {
+        f();
+        batch += 1;
+    }
+    let batch = (batch / 100).max(1);
+
+    let start = Instant::now();
+    let mut iterations = 0u64;
+    while start.elapsed() < MEASURE {
+        for _ in 0..batch {
+            f();
+        }
+        iterations += u64::from(batch);
+    }
+
+    let per_iter = start.elapsed().as_nanos() / u128::from(iterations);
+    println!("{name:<20} {per_iter:>10} ns/iter ({iterations} iterations)");
+}
+
+fn main() {
+    // cargo passes `--bench`, so only take arguments that aren't flags as a filter
+    let filter = std::env::args().skip(1).find(|arg| !arg.starts_with("--"));
+    let filter = filter.as_deref();
+
+    let record = Game::from_string(WORLD_RECORD_DECK);
+    bench(filter, "play_record", || {
+        black_box(black_box(record.clone()).play());
+    });
+
+    let rules = Rules::classic();
+    bench(filter, "play_record_rules", || {
+        black_box(black_box(record.clone()).play_with_rules(black_box(&rules)));
+    });
+
+    let safe_record = SafeGame::from(&record);
+    bench(filter, "play_record_safe", || {
+        black_box(black_box(safe_record.clone()).play());
+    });
+
+    let record_deck: [Card; DECK_SIZE] = [record.p1_cards(), record.p2_cards()]
+        .concat()
+        .try_into()
+        .unwrap();
+    let mut playing = record.clone();
+    bench(filter, "play_record_reset", || {
+        playing.reset_to_deck(black_box(&record_deck));
+        black_box(playing.play());
+    });
+
+    let mut deck = new_deck();
+    let mut seed = 0;
+    bench(filter, "play_random", || {
+        seed += 1;
+        black_box(Game::random_seeded(&mut deck, seed).play());
+    });
+}
@@ -0,0 +1,27 @@
+#![no_main]
+
+use beggar_my_neighbour::{Game, DECK_SIZE};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(string) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    let Ok(mut game) = Game::try_from_string(string) else {
+        return;
+    };
+
+    let cards = game.p1_cards().len() + game.p2_cards().len() + game.middle_len();
+    assert!(cards <= DECK_SIZE, "parsed a game with {cards} cards");
+
+    // a parsed game always prints a notation that parses back to the same game
+    assert_eq!(Game::try_from_string(&format!("{game:?}")), Ok(game.clone()));
+
+    // playing it can't overflow a hand or the middle pile, and never loses or makes up a card
+    game.play_with_limit(10_000);
+    assert_eq!(
+        game.p1_cards().len() + game.p2_cards().len() + game.middle_len(),
+        cards
+    );
+});
@@ -0,0 +1,43 @@
+#![no_main]
+
+use beggar_my_neighbour::{new_deck, Game, Termination, Winner, DECK_SIZE};
+use libfuzzer_sys::{
+    arbitrary::{self, Arbitrary, Unstructured},
+    fuzz_target,
+};
+
+/// A legal deal, from the fuzzer's bytes driving a shuffle of a full deck,
+/// so no input is wasted on a deck the parser would reject
+#[derive(Debug)]
+struct Deal(Game);
+
+impl<'a> Arbitrary<'a> for Deal {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut deck = new_deck();
+        for i in (1..DECK_SIZE).rev() {
+            deck.swap(i, u.int_in_range(0..=i)?);
+        }
+
+        let game = Game::from_hands(&deck[..DECK_SIZE / 2], &deck[DECK_SIZE / 2..])
+            .expect("a shuffled deck is a legal deal");
+        Ok(Self(game))
+    }
+}
+
+fuzz_target!(|deal: Deal| {
+    let Deal(mut game) = deal;
+
+    let stats = game.play_with_limit(10_000);
+
+    // no card is ever lost or made up
+    assert_eq!(
+        game.p1_cards().len() + game.p2_cards().len() + game.middle_len(),
+        DECK_SIZE
+    );
+
+    // the stats agree with the position the game ended on
+    match stats.terminated {
+        Termination::Win(player) => assert_eq!(game.winner(), Winner::from(player)),
+        Termination::LimitReached => assert_eq!(stats.turns, 10_000),
+    }
+});
@@ -0,0 +1,256 @@
+#[cfg(feature = "std")]
+use crate::ParseError;
+#[cfg(feature = "std")]
+use crate::{available_threads, new_deck};
+use crate::{Game, GameStats};
+#[cfg(feature = "std")]
+use crate::{Player, Termination};
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+
+/// Plays `n` random games, returning the stats of each in the order they were played
+#[cfg(feature = "std")]
+#[must_use]
+pub fn run_batch(n: usize) -> Vec<GameStats> {
+    let mut deck = new_deck();
+
+    (0..n).map(|_| Game::random(&mut deck).play()).collect()
+}
+
+/// Plays a copy of every game across [`available_threads`] threads, returning their stats in the same order
+#[cfg(feature = "std")]
+#[must_use]
+pub fn par_play(games: &[Game]) -> Vec<GameStats> {
+    let chunk = games.len().div_ceil(available_threads().get()).max(1);
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = games
+            .chunks(chunk)
+            .map(|games| {
+                scope.spawn(|| {
+                    games
+                        .iter()
+                        .map(|game| game.clone().play())
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+/// How often each player won out of many random deals, from [`win_rate`]
+#[cfg(feature = "std")]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct WinRates {
+    /// How many games were played
+    pub samples: usize,
+    /// How many games player 1, who plays the first card, won
+    pub p1: usize,
+    /// How many games player 2 won
+    pub p2: usize,
+    /// How many games hit [`crate::TURN_LIMIT`] before anyone won, so may be infinite
+    pub unfinished: usize,
+}
+
+#[cfg(feature = "std")]
+impl WinRates {
+    /// The fraction of games player 1 won, or 0 if none were played
+    pub fn p1_rate(&self) -> f64 {
+        self.rate(self.p1)
+    }
+
+    /// The fraction of games player 2 won, or 0 if none were played
+    pub fn p2_rate(&self) -> f64 {
+        self.rate(self.p2)
+    }
+
+    /// The fraction of games that hit the turn limit, or 0 if none were played
+    pub fn unfinished_rate(&self) -> f64 {
+        self.rate(self.unfinished)
+    }
+
+    fn rate(&self, count: usize) -> f64 {
+        if self.samples == 0 {
+            return 0.0;
+        }
+
+        count as f64 / self.samples as f64
+    }
+
+    fn tally(&mut self, stats: &GameStats) {
+        self.samples += 1;
+        match stats.terminated {
+            Termination::Win(Player::P1) => self.p1 += 1,
+            Termination::Win(Player::P2) => self.p2 += 1,
+            Termination::LimitReached => self.unfinished += 1,
+        }
+    }
+}
+
+/// Plays `samples` random deals across [`available_threads`] threads, counting who wins each,
+/// to see whether playing first is an advantage.
+#[cfg(feature = "std")]
+#[must_use]
+pub fn win_rate(samples: usize) -> WinRates {
+    let threads = available_threads().get();
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|thread| {
+                // spread the games so they add up to exactly `samples`
+                let games = samples / threads + usize::from(thread < samples % threads);
+
+                scope.spawn(move || {
+                    let mut deck = new_deck();
+                    let mut rates = WinRates::default();
+                    for _ in 0..games {
+                        rates.tally(&Game::random(&mut deck).play());
+                    }
+                    rates
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .fold(WinRates::default(), |total, rates| WinRates {
+                samples: total.samples + rates.samples,
+                p1: total.p1 + rates.p1,
+                p2: total.p2 + rates.p2,
+                unfinished: total.unfinished + rates.unfinished,
+            })
+    })
+}
+
+/// Parses one deck per line of `reader`, in the `p1/p2` notation of [`Game::try_from_string`].
+///
+/// Every line gives exactly one item, so the `n`th item (counting from 1) is from line `n`, and a bad line
+/// (including a blank one) doesn't stop the rest from being read. An I/O error (including a line that
+/// isn't UTF-8) is given as the last item, so a file that couldn't be read to the end can be told apart
+/// from a short one. A trailing `\r` is ignored, so files with Windows line endings can be read too.
+#[cfg(feature = "std")]
+pub fn parse_decks(
+    reader: impl std::io::BufRead,
+) -> impl Iterator<Item = std::io::Result<Result<Game, ParseError>>> {
+    let mut failed = false;
+
+    reader.lines().map_while(move |line| {
+        if failed {
+            return None;
+        }
+        failed = line.is_err();
+
+        Some(line.map(|line| Game::try_from_string(line.strip_suffix('\r').unwrap_or(&line))))
+    })
+}
+
+/// `n` random games, dealt as they're needed
+#[cfg(feature = "std")]
+pub fn random_games(n: usize) -> impl Iterator<Item = Game> {
+    let mut deck = new_deck();
+
+    (0..n).map(move |_| Game::random(&mut deck))
+}
+
+/// Plays each game as it's reached, yielding it (as it was before being played) alongside its stats.
+///
+/// This is lazy and single-threaded, so it composes with any iterator of games,
+/// like `play_all(random_games(1000)).max_by_key(|(_, stats)| stats.turns)`.
+pub fn play_all<I: Iterator<Item = Game>>(games: I) -> impl Iterator<Item = (Game, GameStats)> {
+    games.map(|game| {
+        let stats = game.clone().play();
+        (game, stats)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::play_all;
+    #[cfg(feature = "std")]
+    use super::{par_play, parse_decks, random_games, run_batch, win_rate, WinRates};
+    use crate::{all_deals, Game, WORLD_RECORD_DECK};
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn plays_every_game() {
+        let batch = run_batch(100);
+
+        assert_eq!(batch.len(), 100);
+        // every deal gives p1 more than one card, so every game lasts more than a turn
+        assert!(batch.iter().all(|stats| stats.turns > 1));
+
+        assert_eq!(play_all(random_games(100)).count(), 100);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn plays_in_parallel() {
+        let games: Vec<_> = random_games(1000).collect();
+        let stats: Vec<_> = games.iter().map(|game| game.clone().play()).collect();
+
+        assert_eq!(par_play(&games), stats);
+        assert!(par_play(&[]).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn counts_wins() {
+        let rates = win_rate(1001);
+        assert_eq!(rates.samples, 1001);
+        assert_eq!(rates.p1 + rates.p2 + rates.unfinished, 1001);
+
+        let total = rates.p1_rate() + rates.p2_rate() + rates.unfinished_rate();
+        assert!((total - 1.0).abs() < 1e-9);
+
+        assert_eq!(win_rate(0), WinRates::default());
+        assert_eq!(WinRates::default().p1_rate(), 0.0);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn parses_a_deck_per_line() {
+        use crate::ParseError;
+
+        let file = "A-K/-QJ\r\n\n-X/-\nJ/";
+        let decks: Vec<_> = parse_decks(file.as_bytes()).map(Result::unwrap).collect();
+
+        assert_eq!(
+            decks,
+            [
+                Ok(Game::from_string("A-K/-QJ")),
+                Err(ParseError::WrongSectionCount(1)),
+                Err(ParseError::InvalidChar {
+                    found: 'X',
+                    offset: 1
+                }),
+                Ok(Game::from_string("J/")),
+            ]
+        );
+
+        // a line that isn't UTF-8 is an error, and ends the decks
+        let file = b"A-K/-QJ\n\xff\nJ/";
+        let mut decks = parse_decks(&file[..]);
+        assert!(matches!(decks.next(), Some(Ok(Ok(_)))));
+        assert!(decks.next().unwrap().is_err());
+        assert!(decks.next().is_none());
+    }
+
+    #[test]
+    fn plays_lazily() {
+        let record = Game::from_string(WORLD_RECORD_DECK);
+        let (game, stats) = play_all([record.clone()].into_iter()).next().unwrap();
+        assert_eq!(game, record);
+        assert_eq!(stats.turns, 8344);
+
+        // the space of deals is far too big to play, so this only works if games are played as they're reached
+        let (game, stats) = play_all(all_deals()).nth(3).unwrap();
+        assert_eq!(game.deal_index(), Some(3));
+        assert_eq!(stats, game.clone().play());
+    }
+}
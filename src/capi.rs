@@ -0,0 +1,118 @@
+//! A C API for playing games from other languages.
+//!
+//! Build it as a shared library with `cargo rustc --release --lib --no-default-features --features std,capi --crate-type cdylib`.
+//! The API itself only needs `core`, but a shared library needs `std` for its panic handler.
+
+use crate::{Card, Game, Player, Termination, DECK_SIZE};
+
+/// The result of [`bmn_play`]
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BmnStats {
+    /// [`crate::GameStats::turns`]
+    pub turns: u64,
+    /// [`crate::GameStats::tricks`]
+    pub tricks: u64,
+    /// 1 if player 1 won, 2 if player 2 won, or 0 if the game didn't finish within [`crate::TURN_LIMIT`]
+    pub winner: u8,
+}
+
+/// [`bmn_play`] succeeded
+pub const BMN_OK: i32 = 0;
+/// A pointer passed to [`bmn_play`] was null
+pub const BMN_NULL: i32 = 1;
+/// The deck passed to [`bmn_play`] wasn't [`DECK_SIZE`] codes long
+pub const BMN_WRONG_LENGTH: i32 = 2;
+/// The deck passed to [`bmn_play`] had a code that isn't a card (see [`Card::from_u8`])
+pub const BMN_INVALID_CARD: i32 = 3;
+/// The deck passed to [`bmn_play`] didn't have one deck's worth of each card
+pub const BMN_INVALID_DECK: i32 = 4;
+
+/// Plays the game dealt from `deck`, written as the value of each card (`card as u8`,
+/// so 4 for an Ace down to 0 for an Other), with the first half going to player 1.
+///
+/// Writes the result to `out` and returns [`BMN_OK`], or returns one of the other `BMN_` codes
+/// without touching `out` if the input is invalid.
+///
+/// # Safety
+///
+/// `deck` must point to `len` readable bytes, and `out` must be valid to write a [`BmnStats`] to.
+#[no_mangle]
+pub unsafe extern "C" fn bmn_play(deck: *const u8, len: usize, out: *mut BmnStats) -> i32 {
+    if deck.is_null() || out.is_null() {
+        return BMN_NULL;
+    }
+
+    if len != DECK_SIZE {
+        return BMN_WRONG_LENGTH;
+    }
+
+    // Safety: the caller guarantees `deck` points to `len` bytes
+    let codes = unsafe { core::slice::from_raw_parts(deck, len) };
+
+    let mut cards = [Card::Other; DECK_SIZE];
+    for (card, &code) in cards.iter_mut().zip(codes) {
+        let Some(parsed) = Card::from_u8(code) else {
+            return BMN_INVALID_CARD;
+        };
+        *card = parsed;
+    }
+
+    let Ok(mut game) = Game::from_hands(&cards[..DECK_SIZE / 2], &cards[DECK_SIZE / 2..]) else {
+        return BMN_INVALID_DECK;
+    };
+
+    let stats = game.play();
+
+    // Safety: the caller guarantees `out` is valid to write to
+    unsafe {
+        out.write(BmnStats {
+            turns: stats.turns as u64,
+            tricks: stats.tricks as u64,
+            winner: match stats.terminated {
+                Termination::Win(Player::P1) => 1,
+                Termination::Win(Player::P2) => 2,
+                Termination::LimitReached => 0,
+            },
+        });
+    }
+
+    BMN_OK
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bmn_play, BmnStats, BMN_INVALID_CARD, BMN_INVALID_DECK, BMN_OK, BMN_WRONG_LENGTH};
+    use crate::{Game, WORLD_RECORD_DECK};
+
+    fn play(codes: &[u8]) -> (i32, Option<BmnStats>) {
+        let mut out = core::mem::MaybeUninit::<BmnStats>::uninit();
+        let code = unsafe { bmn_play(codes.as_ptr(), codes.len(), out.as_mut_ptr()) };
+
+        (code, (code == BMN_OK).then(|| unsafe { out.assume_init() }))
+    }
+
+    #[test]
+    fn plays_the_record() {
+        let record = Game::from_string(WORLD_RECORD_DECK);
+        let codes: Vec<u8> = record.p1_codes().chain(record.p2_codes()).collect();
+
+        let (code, stats) = play(&codes);
+        assert_eq!(code, BMN_OK);
+        assert_eq!(
+            stats,
+            Some(BmnStats {
+                turns: 8344,
+                tricks: 1164,
+                winner: 2
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_decks() {
+        assert_eq!(play(&[0; 10]).0, BMN_WRONG_LENGTH);
+        assert_eq!(play(&[9; 52]).0, BMN_INVALID_CARD);
+        assert_eq!(play(&[0; 52]).0, BMN_INVALID_DECK);
+    }
+}
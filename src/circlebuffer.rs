@@ -1,109 +1,217 @@
-use std::{mem::MaybeUninit, ptr::copy_nonoverlapping};
-
-/// Capacity for all `CircularBuffers`.
-/// This size satisfies the following restrictions:
-/// - Capacity MUST be a power of 2 (for fast modulo).
-/// - Capacity should be >= the size of the deck (52 cards).
-const CAPACITY: usize = 64;
-
-// TODO: optimize by virtual memory
-/// A circular buffer.
-#[derive(Debug, Clone, Copy)]
-pub struct CircularBuffer<T> {
+use core::{
+    fmt::Debug,
+    hash::{Hash, Hasher},
+    mem::MaybeUninit,
+    ptr::copy_nonoverlapping,
+};
+
+/// The error returned when pushing to a full `CircularBuffer`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError;
+
+/// A circular buffer holding up to `CAP` items.
+///
+/// `CAP` MUST be a power of 2 (for fast modulo), which is checked at compile time.
+///
+/// Every item is written twice, to `data` and to the same index of `mirror` straight after it
+/// (the same trick as mapping a ring buffer twice in virtual memory), so the items can always be
+/// borrowed as one contiguous slice, even once they wrap around the end of `data`.
+///
+/// Slots are left uninitialized until they're first written, and only the `len` items from `head`
+/// (in `data`, or carrying on into `mirror`) are ever read.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct CircularBuffer<T: Copy, const CAP: usize> {
+    data: [MaybeUninit<T>; CAP],
+    mirror: [MaybeUninit<T>; CAP],
     head: usize,
     len: usize,
-    data: [T; CAPACITY],
 }
 
-impl<T: Copy> CircularBuffer<T> {
+impl<T: Copy, const CAP: usize> CircularBuffer<T, CAP> {
+    /// Referenced by every constructor, so a bad `CAP` fails to compile as soon as it's used
+    const CAP_IS_POWER_OF_TWO: () = assert!(
+        CAP.is_power_of_two(),
+        "CircularBuffer capacity must be a power of 2"
+    );
+
     pub const fn new() -> Self {
+        let () = Self::CAP_IS_POWER_OF_TWO;
+
         Self {
+            data: [const { MaybeUninit::uninit() }; CAP],
+            mirror: [const { MaybeUninit::uninit() }; CAP],
             head: 0,
             len: 0,
-            data: unsafe { MaybeUninit::uninit().assume_init() },
         }
     }
 
-    /// Get a `SliceFifo` from a pointer to a slice of length M, where M <= N.
+    /// Copies a slice into a new `CircularBuffer`, or fails if it has more than `CAP` items.
     ///
-    /// It does not make any assumptions in production about the length of the slice.
-    pub unsafe fn from_memory(source: *const T, len: usize) -> Self {
-        debug_assert!(len <= CAPACITY, "SliceFifo::from_slice: slice is too long!");
-        let mut data = [std::mem::zeroed(); CAPACITY];
-        copy_nonoverlapping(source, data.as_mut_ptr(), len);
-        Self { head: 0, len, data }
+    /// The length is checked in release builds too, as this is how parsed (so untrusted) hands are built.
+    pub fn from_slice(items: &[T]) -> Result<Self, CapacityError> {
+        let mut buffer = Self::new();
+        buffer.refill(items)?;
+        Ok(buffer)
+    }
+
+    /// Replaces the contents with a slice, like [`Self::from_slice`] but without building
+    /// a new buffer. Fails, leaving the buffer as it was, if it has more than `CAP` items.
+    pub fn refill(&mut self, items: &[T]) -> Result<(), CapacityError> {
+        if items.len() > CAP {
+            return Err(CapacityError);
+        }
+
+        self.head = 0;
+        self.len = items.len();
+        // Safety: we just checked that the items fit
+        unsafe { self.write(0, items.as_ptr(), items.len()) };
+        Ok(())
+    }
+
+    /// Copies `count` items to both `data` and `mirror`, starting at `index`.
+    /// `index + count` must not be more than `CAP`.
+    #[inline(always)]
+    unsafe fn write(&mut self, index: usize, source: *const T, count: usize) {
+        copy_nonoverlapping(source, self.data.as_mut_ptr().cast::<T>().add(index), count);
+        copy_nonoverlapping(
+            source,
+            self.mirror.as_mut_ptr().cast::<T>().add(index),
+            count,
+        );
     }
 
     pub unsafe fn push(&mut self, item: T) {
         debug_assert!(
-            self.len < CAPACITY,
-            "SliceFifo::push: slice is full! (len = {}, CAPACITY = {})",
+            self.len < CAP,
+            "CircularBuffer::push: buffer is full! (len = {}, CAP = {})",
             self.len,
-            CAPACITY
+            CAP
         );
 
-        // We use a bitwise operator where N is a power of 2 instead of modulo
-        let tail = (self.head + self.len) & (CAPACITY - 1);
+        // We use a bitwise operator where CAP is a power of 2 instead of modulo
+        let tail = (self.head + self.len) & (CAP - 1);
 
-        // This is safe because we know that the length of the slice is less than N (because of % N)
-        *self.data.get_unchecked_mut(tail) = item;
+        // This is safe because we know that the length of the slice is less than CAP (because of % CAP)
+        *self.data.get_unchecked_mut(tail) = MaybeUninit::new(item);
+        *self.mirror.get_unchecked_mut(tail) = MaybeUninit::new(item);
 
         // But this is not safe, because we don't know if the slice is full or not
         self.len += 1;
     }
 
+    /// Pushes an item, or returns an error if the buffer is already full.
+    ///
+    /// This is the safe counterpart to [`CircularBuffer::push`], for code outside the game loop.
+    pub fn try_push(&mut self, item: T) -> Result<(), CapacityError> {
+        if self.len == CAP {
+            return Err(CapacityError);
+        }
+
+        // Safety: we just checked that there is room
+        unsafe { self.push(item) };
+        Ok(())
+    }
+
     pub unsafe fn push_slice(&mut self, slice: &[T]) {
         debug_assert!(
-            self.len + slice.len() <= CAPACITY,
-            "SliceFifo::push_slice: slice is too long!"
+            self.len + slice.len() <= CAP,
+            "CircularBuffer::push_slice: slice is too long!"
         );
 
-        debug_assert!(!slice.is_empty(), "SliceFifo::push_slice: slice is empty!");
+        debug_assert!(
+            !slice.is_empty(),
+            "CircularBuffer::push_slice: slice is empty!"
+        );
 
-        // We use a bitwise operator where N is a power of 2 instead of modulo
-        let tail = (self.head + self.len) & (CAPACITY - 1);
-        if slice.len() > CAPACITY - tail {
+        // We use a bitwise operator where CAP is a power of 2 instead of modulo
+        let tail = (self.head + self.len) & (CAP - 1);
+        if slice.len() > CAP - tail {
             // We need to split the slice into two parts
-            copy_nonoverlapping(
-                slice.as_ptr(),
-                self.data.as_mut_ptr().add(tail),
-                CAPACITY - tail,
-            );
-            copy_nonoverlapping(
-                slice.as_ptr().add(CAPACITY - tail),
-                self.data.as_mut_ptr(),
-                slice.len() - (CAPACITY - tail),
+            self.write(tail, slice.as_ptr(), CAP - tail);
+            self.write(
+                0,
+                slice.as_ptr().add(CAP - tail),
+                slice.len() - (CAP - tail),
             );
         } else {
             // We can just copy the slice into the buffer
-            copy_nonoverlapping(
-                slice.as_ptr(),
-                self.data.as_mut_ptr().add(tail),
-                slice.len(),
-            );
+            self.write(tail, slice.as_ptr(), slice.len());
         }
         self.len += slice.len();
     }
 
     /// Skips bounds checking. If the buffer is empty, this will be UB.
     pub unsafe fn pop_unchecked(&mut self) -> T {
-        debug_assert!(self.len > 0, "SliceFifo::pop_unchecked: slice is empty!");
-        let item = self.data.get_unchecked(self.head);
-        if self.head == CAPACITY - 1 {
+        debug_assert!(
+            self.len > 0,
+            "CircularBuffer::pop_unchecked: buffer is empty!"
+        );
+        // Safety: the front item was written when it was pushed
+        let item = self.data.get_unchecked(self.head).assume_init();
+        if self.head == CAP - 1 {
             self.head = 0;
         } else {
             self.head += 1;
         }
         self.len -= 1;
-        *item
+        item
     }
 
-    pub fn slice(&self) -> &[T] {
-        let tail = (self.head + self.len) % CAPACITY;
-        if self.head <= tail {
-            &self.data[self.head..tail]
-        } else {
-            &self.data[self.head..CAPACITY]
+    /// Pushes an item to the front, so it is the next to be popped.
+    #[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+    pub unsafe fn push_front(&mut self, item: T) {
+        debug_assert!(
+            self.len < CAP,
+            "CircularBuffer::push_front: buffer is full! (len = {}, CAP = {})",
+            self.len,
+            CAP
+        );
+
+        self.head = (self.head + CAP - 1) & (CAP - 1);
+        *self.data.get_unchecked_mut(self.head) = MaybeUninit::new(item);
+        *self.mirror.get_unchecked_mut(self.head) = MaybeUninit::new(item);
+        self.len += 1;
+    }
+
+    /// Removes items from the back until at most `len` remain.
+    #[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+    pub fn truncate(&mut self, len: usize) {
+        self.len = self.len.min(len);
+    }
+
+    /// Pops the front item, or returns `None` if the buffer is empty.
+    pub fn try_pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        // Safety: we just checked that there is an item
+        Some(unsafe { self.pop_unchecked() })
+    }
+
+    /// Moves the first `n` items to the back, keeping their order (like cutting a deck).
+    pub fn rotate(&mut self, n: usize) {
+        if self.len == 0 {
+            return;
+        }
+
+        for _ in 0..n % self.len {
+            if let Some(item) = self.try_pop() {
+                // Safety: we just popped, so there is room
+                unsafe { self.push(item) };
+            }
+        }
+    }
+
+    /// All items in the buffer, from front to back
+    pub const fn slice(&self) -> &[T] {
+        // Safety: `mirror` directly follows `data` (`repr(C)`, and two `[T; CAP]`s need no padding),
+        // and the pointer is derived from the whole struct, so it may read past `data` into `mirror`.
+        // `head < CAP`, so `head + len < 2 * CAP`, and every item was written to both arrays,
+        // so all of them are initialized. `MaybeUninit<T>` has the same layout as `T`.
+        unsafe {
+            core::slice::from_raw_parts((self as *const Self).cast::<T>().add(self.head), self.len)
         }
     }
 
@@ -116,14 +224,98 @@ impl<T: Copy> CircularBuffer<T> {
     }
 }
 
-impl<T: Copy> FromIterator<T> for CircularBuffer<T> {
+/// Only the items are shown, as the slots around them may not be initialized
+impl<T: Copy + Debug, const CAP: usize> Debug for CircularBuffer<T, CAP> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.slice()).finish()
+    }
+}
+
+/// Buffers are equal when they hold the same items in the same order, wherever they sit in the backing array
+impl<T: Copy + PartialEq, const CAP: usize> PartialEq for CircularBuffer<T, CAP> {
+    fn eq(&self, other: &Self) -> bool {
+        self.slice() == other.slice()
+    }
+}
+
+impl<T: Copy + Eq, const CAP: usize> Eq for CircularBuffer<T, CAP> {}
+
+impl<T: Copy + Hash, const CAP: usize> Hash for CircularBuffer<T, CAP> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.slice().hash(state);
+    }
+}
+
+impl<T: Copy, const CAP: usize> FromIterator<T> for CircularBuffer<T, CAP> {
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
         let mut fifo = Self::new();
         for item in iter {
-            unsafe {
-                fifo.push(item);
-            }
+            fifo.try_push(item)
+                .expect("CircularBuffer::from_iter: too many items!");
         }
         fifo
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{CapacityError, CircularBuffer};
+
+    #[test]
+    fn checked_push_and_pop() {
+        let mut buffer = CircularBuffer::<u8, 2>::new();
+
+        assert_eq!(buffer.try_pop(), None);
+        assert_eq!(buffer.try_push(1), Ok(()));
+        assert_eq!(buffer.try_push(2), Ok(()));
+        assert_eq!(buffer.try_push(3), Err(CapacityError));
+
+        assert_eq!(buffer.try_pop(), Some(1));
+        assert_eq!(buffer.try_pop(), Some(2));
+        assert_eq!(buffer.try_pop(), None);
+    }
+
+    #[test]
+    fn slices_must_fit() {
+        let mut buffer = CircularBuffer::<u8, 4>::from_slice(&[1, 2, 3, 4]).unwrap();
+        assert_eq!(
+            CircularBuffer::<u8, 4>::from_slice(&[1, 2, 3, 4, 5]).unwrap_err(),
+            CapacityError
+        );
+
+        assert_eq!(buffer.refill(&[5, 6, 7, 8, 9]), Err(CapacityError));
+        assert_eq!(buffer.slice(), [1, 2, 3, 4]);
+        assert_eq!(buffer.refill(&[5, 6]), Ok(()));
+        assert_eq!(buffer.slice(), [5, 6]);
+    }
+
+    #[test]
+    fn slice_across_wraparound() {
+        let mut buffer = CircularBuffer::<u8, 8>::new();
+
+        // push and pop enough that the front moves past the end of the backing array
+        for value in 0..6 {
+            buffer.try_push(value).unwrap();
+        }
+        for _ in 0..5 {
+            buffer.try_pop();
+        }
+        unsafe { buffer.push_slice(&[6, 7, 8, 9, 10]) };
+        buffer.try_push(11).unwrap();
+
+        assert_eq!(buffer.slice(), [5, 6, 7, 8, 9, 10, 11]);
+        assert_eq!(
+            buffer.iter().copied().collect::<Vec<_>>(),
+            [5, 6, 7, 8, 9, 10, 11]
+        );
+
+        buffer.rotate(3);
+        assert_eq!(buffer.slice(), [8, 9, 10, 11, 5, 6, 7]);
+
+        // equality only looks at the items, not where they sit in the backing array
+        let fresh: CircularBuffer<u8, 8> = [8, 9, 10, 11, 5, 6, 7].into_iter().collect();
+        assert_eq!(buffer, fresh);
+        buffer.try_pop();
+        assert_ne!(buffer, fresh);
+    }
+}
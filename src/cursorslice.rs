@@ -1,30 +1,53 @@
-use std::mem::MaybeUninit;
+use core::{
+    fmt::Debug,
+    hash::{Hash, Hasher},
+    mem::MaybeUninit,
+};
 
 /// An optimized structure trading off memory for speed.
 /// It is a slice that has a cursor that navigates around, which only supports push and clear.
-#[derive(Debug, Clone, Copy)]
+///
+/// Everything past the cursor may be uninitialized, so only the items before it are ever read.
+#[derive(Clone, Copy)]
 pub struct CursorSlice<T: Copy, const N: usize> {
-    data: [T; N],
+    data: [MaybeUninit<T>; N],
     cursor: usize,
 }
 
 impl<T: Copy, const N: usize> CursorSlice<T, N> {
     pub const fn new() -> Self {
         Self {
-            data: unsafe { MaybeUninit::uninit().assume_init() },
+            data: [const { MaybeUninit::uninit() }; N],
             cursor: 0,
         }
     }
 
     /// This is fully unsafe! We are assuming that the cursor is always in bounds in release mode.
+    ///
+    /// # Safety
+    ///
+    /// The slice must have room for another value (`len() < N`).
     pub unsafe fn push_unchecked(&mut self, value: T) {
         debug_assert!(self.cursor < N, "CursorSlice is full!");
-        *self.data.get_unchecked_mut(self.cursor) = value;
+        *self.data.get_unchecked_mut(self.cursor) = MaybeUninit::new(value);
         self.cursor += 1;
     }
 
     pub const fn slice(&self) -> &[T] {
-        unsafe { std::slice::from_raw_parts(self.data.as_ptr(), self.cursor) }
+        // Safety: every item before the cursor was pushed, so is initialized,
+        // and `MaybeUninit<T>` has the same layout as `T`
+        unsafe { core::slice::from_raw_parts(self.data.as_ptr().cast::<T>(), self.cursor) }
+    }
+
+    #[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+    pub fn pop(&mut self) -> Option<T> {
+        if self.cursor == 0 {
+            return None;
+        }
+
+        self.cursor -= 1;
+        // Safety: the item was pushed before the cursor moved past it
+        Some(unsafe { self.data.get_unchecked(self.cursor).assume_init() })
     }
 
     pub fn clear(&mut self) {
@@ -35,7 +58,57 @@ impl<T: Copy, const N: usize> CursorSlice<T, N> {
         self.slice().iter()
     }
 
+    pub const fn len(&self) -> usize {
+        self.cursor
+    }
+
     pub const fn is_empty(&self) -> bool {
         self.cursor == 0
     }
 }
+
+/// Only the pushed items are shown, as the rest may not be initialized
+impl<T: Copy + Debug, const N: usize> Debug for CursorSlice<T, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.slice()).finish()
+    }
+}
+
+/// Only the pushed items are compared, not whatever is left past the cursor
+impl<T: Copy + PartialEq, const N: usize> PartialEq for CursorSlice<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.slice() == other.slice()
+    }
+}
+
+impl<T: Copy + Eq, const N: usize> Eq for CursorSlice<T, N> {}
+
+impl<T: Copy + Hash, const N: usize> Hash for CursorSlice<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.slice().hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CursorSlice;
+
+    #[test]
+    fn push_and_clear() {
+        let mut slice = CursorSlice::<u8, 4>::new();
+        assert!(slice.is_empty());
+
+        for value in 1..=4 {
+            unsafe { slice.push_unchecked(value) };
+        }
+        assert_eq!(slice.slice(), [1, 2, 3, 4]);
+        assert_eq!(slice.len(), 4);
+
+        slice.clear();
+        assert!(slice.is_empty());
+        assert_eq!(slice.slice(), []);
+
+        unsafe { slice.push_unchecked(5) };
+        assert_eq!(slice.slice(), [5]);
+    }
+}
@@ -0,0 +1,197 @@
+//! Random deals where player 1 starts with a chosen number of some penalty cards.
+
+use crate::{Card, Game, InvalidDeck, DECK_SIZE};
+use core::fmt::{Display, Formatter};
+
+/// Builds [`Deals`], an endless stream of random deals constrained by what player 1 is dealt.
+///
+/// Each deal is equally likely out of every deal meeting the constraints, as every card that isn't
+/// constrained is still shuffled freely between the two hands.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct DealBuilder {
+    /// How many of each card (indexed by `card as u8`) p1 must be dealt, if constrained
+    p1: [Option<usize>; Card::ALL.len()],
+    seed: Option<u64>,
+}
+
+impl DealBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Deals p1 exactly `count` Aces
+    #[must_use]
+    pub const fn p1_aces(self, count: usize) -> Self {
+        self.p1_count(Card::Ace, count)
+    }
+
+    /// Deals p1 exactly `count` Kings
+    #[must_use]
+    pub const fn p1_kings(self, count: usize) -> Self {
+        self.p1_count(Card::King, count)
+    }
+
+    /// Deals p1 exactly `count` Queens
+    #[must_use]
+    pub const fn p1_queens(self, count: usize) -> Self {
+        self.p1_count(Card::Queen, count)
+    }
+
+    /// Deals p1 exactly `count` Jacks
+    #[must_use]
+    pub const fn p1_jacks(self, count: usize) -> Self {
+        self.p1_count(Card::Jack, count)
+    }
+
+    const fn p1_count(mut self, card: Card, count: usize) -> Self {
+        self.p1[card as usize] = Some(count);
+        self
+    }
+
+    /// Shuffles with a fixed seed, so the same builder always deals the same games
+    #[must_use]
+    pub const fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Checks the constraints can be met, returning the first card p1 was asked for more of than a deck has.
+    ///
+    /// Without the `std` feature, there's no entropy to shuffle with, so a [`DealBuilder::seed`] must be set,
+    /// or [`DealError::MissingSeed`] is returned.
+    pub fn build(self) -> Result<Deals, DealError> {
+        for card in Card::ALL {
+            if let Some(found) = self.p1[card as usize] {
+                if found > card.deck_count() {
+                    return Err(DealError::InvalidDeck(InvalidDeck {
+                        card,
+                        expected: card.deck_count(),
+                        found,
+                    }));
+                }
+            }
+        }
+
+        let rng = match self.seed {
+            Some(seed) => fastrand::Rng::with_seed(seed),
+            #[cfg(feature = "std")]
+            None => fastrand::Rng::new(),
+            #[cfg(not(feature = "std"))]
+            None => return Err(DealError::MissingSeed),
+        };
+
+        Ok(Deals { p1: self.p1, rng })
+    }
+}
+
+/// The error returned by [`DealBuilder::build`] when it can't deal any games
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DealError {
+    /// p1 was asked for more of a card than a deck has
+    InvalidDeck(InvalidDeck),
+    /// No [`DealBuilder::seed`] was set, which is needed to shuffle without the `std` feature
+    MissingSeed,
+}
+
+impl Display for DealError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidDeck(invalid) => write!(f, "{invalid}"),
+            Self::MissingSeed => write!(f, "a seed is needed to deal without std"),
+        }
+    }
+}
+
+impl core::error::Error for DealError {}
+
+/// Random deals meeting the constraints of a [`DealBuilder`]. This never runs out.
+#[derive(Debug, Clone)]
+pub struct Deals {
+    p1: [Option<usize>; Card::ALL.len()],
+    rng: fastrand::Rng,
+}
+
+impl Iterator for Deals {
+    type Item = Game;
+
+    fn next(&mut self) -> Option<Game> {
+        let (mut deck, mut p1_len, mut p2_len) = ([Card::Other; DECK_SIZE], 0, 0);
+        let (mut free, mut free_len) = ([Card::Other; DECK_SIZE], 0);
+
+        // Constrained cards go straight into their hands, and the rest are shuffled between them.
+        // Only penalty cards can be constrained, so neither hand can get more than half a deck.
+        for card in Card::ALL {
+            let (p1, p2) = match self.p1[card as usize] {
+                Some(count) => (count, card.deck_count() - count),
+                None => {
+                    free[free_len..free_len + card.deck_count()].fill(card);
+                    free_len += card.deck_count();
+                    continue;
+                }
+            };
+
+            deck[p1_len..p1_len + p1].fill(card);
+            p1_len += p1;
+            deck[DECK_SIZE / 2 + p2_len..DECK_SIZE / 2 + p2_len + p2].fill(card);
+            p2_len += p2;
+        }
+
+        let free = &mut free[..free_len];
+        self.rng.shuffle(free);
+
+        let (p1_free, p2_free) = free.split_at(DECK_SIZE / 2 - p1_len);
+        deck[p1_len..DECK_SIZE / 2].copy_from_slice(p1_free);
+        deck[DECK_SIZE / 2 + p2_len..].copy_from_slice(p2_free);
+
+        let (p1, p2) = deck.split_at_mut(DECK_SIZE / 2);
+        self.rng.shuffle(p1);
+        self.rng.shuffle(p2);
+
+        Some(Game::deal(&deck))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DealBuilder, DealError};
+    use crate::{Card, InvalidDeck};
+
+    #[test]
+    fn deals_meet_constraints() {
+        let deals = DealBuilder::new().p1_aces(3).p1_jacks(0).seed(66).build();
+
+        for game in deals.unwrap().take(200) {
+            assert!(game.validate().is_ok());
+            assert_eq!(game.p1_cards().len(), 26);
+
+            let (p1, _) = game.hand_counts();
+            assert_eq!(p1.count(Card::Ace), 3);
+            assert_eq!(p1.count(Card::Jack), 0);
+        }
+
+        let seeded = || DealBuilder::new().p1_kings(4).seed(1).build().unwrap();
+        assert!(seeded().take(10).eq(seeded().take(10)));
+    }
+
+    #[test]
+    fn unsatisfiable_constraints() {
+        assert_eq!(
+            DealBuilder::new().p1_aces(5).seed(0).build().unwrap_err(),
+            DealError::InvalidDeck(InvalidDeck {
+                card: Card::Ace,
+                expected: 4,
+                found: 5
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "std"))]
+    fn needs_a_seed_without_std() {
+        assert_eq!(
+            DealBuilder::new().build().unwrap_err(),
+            DealError::MissingSeed
+        );
+    }
+}
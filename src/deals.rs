@@ -0,0 +1,217 @@
+//! Ranking every possible deal, so the whole space of games can be indexed into, split up and resumed.
+//!
+//! Deals are ordered lexicographically by card value (`card as u8`), looking at p1's hand and then p2's,
+//! so index 0 is every Other followed by the Jacks, Queens, Kings and then Aces.
+
+use crate::{new_deck, Card, Game, Player, DECK_SIZE};
+
+/// How many distinct deals there are, `52! / (36! 4! 4! 4! 4!)`
+pub const DEAL_COUNT: u128 = arrangements(&deck_counts());
+
+/// How many of each card (indexed by `card as u8`) are in a deck
+const fn deck_counts() -> [usize; Card::ALL.len()] {
+    let mut counts = [0; Card::ALL.len()];
+
+    let mut i = 0;
+    while i < Card::ALL.len() {
+        counts[Card::ALL[i] as usize] = Card::ALL[i].deck_count();
+        i += 1;
+    }
+
+    counts
+}
+
+/// How many distinct orderings there are of a multiset with the given counts
+pub(crate) const fn arrangements(counts: &[usize; Card::ALL.len()]) -> u128 {
+    let mut arrangements = 1;
+    let mut remaining = 0;
+
+    // The multinomial coefficient, as a product of binomial coefficients.
+    // Each partial product is itself a binomial coefficient, so the division is always exact.
+    let mut card = 0;
+    while card < counts.len() {
+        let mut i = 0;
+        while i < counts[card] as u128 {
+            remaining += 1;
+            arrangements = arrangements * remaining / (i + 1);
+            i += 1;
+        }
+        card += 1;
+    }
+
+    arrangements
+}
+
+/// Steps `cards` to the next distinct ordering by value, returning `false` once every ordering has been seen.
+pub(crate) fn next_arrangement(cards: &mut [Card]) -> bool {
+    let Some(pivot) = (1..cards.len())
+        .rev()
+        .find(|&i| (cards[i - 1] as u8) < (cards[i] as u8))
+        .map(|i| i - 1)
+    else {
+        return false;
+    };
+
+    let swap = (pivot + 1..cards.len())
+        .rev()
+        .find(|&i| (cards[pivot] as u8) < (cards[i] as u8))
+        .unwrap();
+
+    cards.swap(pivot, swap);
+    cards[pivot + 1..].reverse();
+
+    true
+}
+
+/// Every deal, in order. See [`all_deals_from`] to start part way through.
+pub fn all_deals() -> impl Iterator<Item = Game> {
+    all_deals_from(0)
+}
+
+/// Every deal from `index` (as in [`Game::from_deal_index`]) onwards, in order
+pub fn all_deals_from(index: u128) -> impl Iterator<Item = Game> {
+    let mut next = unrank(index);
+
+    core::iter::from_fn(move || {
+        let deck = next.as_mut()?;
+        let game = Game::deal(deck);
+
+        if !next_arrangement(deck) {
+            next = None;
+        }
+
+        Some(game)
+    })
+}
+
+/// The deck at the given position in the ordering, or `None` if it's past the last deal
+fn unrank(mut index: u128) -> Option<[Card; DECK_SIZE]> {
+    if index >= DEAL_COUNT {
+        return None;
+    }
+
+    let mut deck = new_deck();
+    let mut counts = deck_counts();
+
+    for slot in &mut deck {
+        for card in Card::ALL.into_iter().rev() {
+            if counts[card as usize] == 0 {
+                continue;
+            }
+
+            // How many deals put this card here, given what's already been placed
+            counts[card as usize] -= 1;
+            let starting_here = arrangements(&counts);
+
+            if index < starting_here {
+                *slot = card;
+                break;
+            }
+
+            index -= starting_here;
+            counts[card as usize] += 1;
+        }
+    }
+
+    Some(deck)
+}
+
+impl Game {
+    /// The deal at position `index` of [`all_deals`], or `None` if `index` isn't below [`DEAL_COUNT`]
+    #[must_use]
+    pub fn from_deal_index(index: u128) -> Option<Self> {
+        unrank(index).map(|deck| Self::deal(&deck))
+    }
+
+    /// The position of this game in [`all_deals`], the inverse of [`Game::from_deal_index`].
+    ///
+    /// Only fresh deals are ranked, so this is `None` unless both players hold half of exactly one deck,
+    /// the middle pile is empty, no penalty is being paid and it's player 1's turn.
+    #[must_use]
+    pub fn deal_index(&self) -> Option<u128> {
+        let fresh = self.p1.len() == DECK_SIZE / 2
+            && self.middle.is_empty()
+            && self.penalty == 0
+            && self.to_move == Player::P1;
+        if !fresh || self.validate().is_err() {
+            return None;
+        }
+
+        let mut index = 0;
+        let mut counts = deck_counts();
+
+        for &placed in self.p1.iter().chain(self.p2.iter()) {
+            // Every deal with a lower card here comes first
+            for card in Card::ALL.into_iter().rev() {
+                if card == placed {
+                    break;
+                }
+
+                if counts[card as usize] > 0 {
+                    counts[card as usize] -= 1;
+                    index += arrangements(&counts);
+                    counts[card as usize] += 1;
+                }
+            }
+
+            counts[placed as usize] -= 1;
+        }
+
+        Some(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{all_deals, all_deals_from, DEAL_COUNT};
+    use crate::{Game, WORLD_RECORD_DECK};
+
+    #[test]
+    fn deal_indices_round_trip() {
+        assert_eq!(DEAL_COUNT, 653_534_134_886_878_245_000);
+
+        let first = Game::from_deal_index(0).unwrap();
+        assert_eq!(
+            format!("{first:?}"),
+            format!("{}/{}JJJJQQQQKKKKAAAA", "-".repeat(26), "-".repeat(10))
+        );
+
+        let last = Game::from_deal_index(DEAL_COUNT - 1).unwrap();
+        assert_eq!(
+            format!("{last:?}"),
+            format!("AAAAKKKKQQQQJJJJ----------/{}", "-".repeat(26))
+        );
+        assert_eq!(last.deal_index(), Some(DEAL_COUNT - 1));
+        assert!(Game::from_deal_index(DEAL_COUNT).is_none());
+
+        let record = Game::from_string(WORLD_RECORD_DECK);
+        let index = record.deal_index().unwrap();
+        assert_eq!(
+            format!("{:?}", Game::from_deal_index(index).unwrap()),
+            format!("{record:?}")
+        );
+
+        // only fresh deals have an index
+        assert_eq!(Game::from_string("A-K/-QJ").deal_index(), None);
+        assert_eq!(
+            Game::from_string(&format!("{WORLD_RECORD_DECK}@2")).deal_index(),
+            None
+        );
+        assert_eq!(
+            Game::from_string(&format!("{WORLD_RECORD_DECK}+2")).deal_index(),
+            None
+        );
+    }
+
+    #[test]
+    fn iterating_matches_indexing() {
+        for (index, game) in all_deals().take(100).enumerate() {
+            assert_eq!(game.deal_index(), Some(index as u128));
+        }
+
+        let resumed: Vec<_> = all_deals_from(DEAL_COUNT - 3)
+            .map(|game| game.deal_index().unwrap())
+            .collect();
+        assert_eq!(resumed, [DEAL_COUNT - 3, DEAL_COUNT - 2, DEAL_COUNT - 1]);
+    }
+}
@@ -0,0 +1,109 @@
+//! Decks with a different number of each card, like a double deck or one with the Others stripped out.
+
+use crate::{Card, DECK_SIZE};
+
+/// How many of each card a deck has. [`DeckSpec::STANDARD`] is the usual 52 card deck.
+///
+/// [`Game`](crate::Game) always holds exactly one standard deck, which is what lets it play
+/// from fixed size buffers. Decks built from any other spec with [`new_deck_spec`] are played
+/// with [`SafeGame::deal`](crate::safe::SafeGame::deal) instead, which has no limit on how many cards it holds.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DeckSpec {
+    pub aces: usize,
+    pub kings: usize,
+    pub queens: usize,
+    pub jacks: usize,
+    pub others: usize,
+}
+
+impl DeckSpec {
+    /// One of each card, as [`new_deck`](crate::new_deck) deals
+    pub const STANDARD: Self = Self {
+        aces: Card::Ace.deck_count(),
+        kings: Card::King.deck_count(),
+        queens: Card::Queen.deck_count(),
+        jacks: Card::Jack.deck_count(),
+        others: Card::Other.deck_count(),
+    };
+
+    /// How many of `card` the deck has
+    pub const fn count(&self, card: Card) -> usize {
+        match card {
+            Card::Ace => self.aces,
+            Card::King => self.kings,
+            Card::Queen => self.queens,
+            Card::Jack => self.jacks,
+            Card::Other => self.others,
+        }
+    }
+
+    /// How many cards the deck has in total
+    pub const fn len(&self) -> usize {
+        self.aces + self.kings + self.queens + self.jacks + self.others
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Every card multiplied `decks` times, so `STANDARD.times(2)` is a double deck
+    #[must_use]
+    pub const fn times(&self, decks: usize) -> Self {
+        Self {
+            aces: self.aces * decks,
+            kings: self.kings * decks,
+            queens: self.queens * decks,
+            jacks: self.jacks * decks,
+            others: self.others * decks,
+        }
+    }
+}
+
+impl Default for DeckSpec {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+const _: () = assert!(DeckSpec::STANDARD.len() == DECK_SIZE);
+
+/// An unshuffled deck following `spec`, ordered like [`new_deck`](crate::new_deck)
+/// from the Aces down to the Others.
+#[cfg(feature = "alloc")]
+pub fn new_deck_spec(spec: DeckSpec) -> alloc::vec::Vec<Card> {
+    Card::ALL
+        .into_iter()
+        .flat_map(|card| core::iter::repeat_n(card, spec.count(card)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DeckSpec;
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn builds_decks() {
+        use super::new_deck_spec;
+        use crate::{new_deck, Card};
+
+        assert_eq!(new_deck_spec(DeckSpec::default()), new_deck());
+
+        let double = new_deck_spec(DeckSpec::STANDARD.times(2));
+        assert_eq!(double.len(), 104);
+        assert_eq!(double.iter().filter(|&&card| card == Card::Ace).count(), 8);
+
+        let faces = DeckSpec {
+            others: 0,
+            ..DeckSpec::STANDARD
+        };
+        assert_eq!(new_deck_spec(faces).len(), 16);
+    }
+
+    #[test]
+    fn counts() {
+        assert_eq!(DeckSpec::STANDARD.len(), 52);
+        assert_eq!(DeckSpec::STANDARD.times(0), DeckSpec::default().times(0));
+        assert!(DeckSpec::STANDARD.times(0).is_empty());
+    }
+}
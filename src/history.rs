@@ -0,0 +1,132 @@
+use crate::{Game, Player, Turn};
+use alloc::vec::Vec;
+
+/// Everything needed to put a step back
+#[derive(Debug, Copy, Clone)]
+struct Entry {
+    turn: Turn,
+    /// The penalty before the card was played
+    penalty: u8,
+    /// How many cards were collected from the middle pile (0 if none)
+    collected: usize,
+}
+
+/// A game that records each step, so it can be rewound with [`RecordedGame::undo`].
+///
+/// Recording costs a little memory per card played, which is why it is opt-in
+/// rather than part of [`Game`] (see [`Game::with_history`]).
+#[derive(Debug, Clone)]
+pub struct RecordedGame {
+    game: Game,
+    history: Vec<Entry>,
+}
+
+impl RecordedGame {
+    #[must_use]
+    pub const fn new(game: Game) -> Self {
+        Self {
+            game,
+            history: Vec::new(),
+        }
+    }
+
+    /// The current position
+    pub const fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// How many steps have been played (and can be undone)
+    pub fn len(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+
+    /// Plays a single card, like [`Game::step`], remembering how to undo it.
+    pub fn step(&mut self) -> Option<Turn> {
+        let penalty = self.game.penalty;
+        let pile = self.game.middle.len();
+
+        let turn = self.game.step()?;
+
+        self.history.push(Entry {
+            turn,
+            penalty,
+            // the card that failed to pay the penalty is collected too
+            collected: if turn.collected { pile + 1 } else { 0 },
+        });
+
+        Some(turn)
+    }
+
+    /// Takes back the last step, returning it, or `None` if we're back at the start.
+    pub fn undo(&mut self) -> Option<Turn> {
+        let Entry {
+            turn,
+            penalty,
+            collected,
+        } = self.history.pop()?;
+
+        let game = &mut self.game;
+        let (player, other) = match turn.player {
+            Player::P1 => (&mut game.p1, &mut game.p2),
+            Player::P2 => (&mut game.p2, &mut game.p1),
+        };
+
+        // The collected pile is the back of the other player's hand, in the order it was played
+        if collected > 0 {
+            let kept = other.len() - collected;
+            for &card in &other.slice()[kept..] {
+                // Safety: these cards came out of the middle pile, so it has room for them
+                unsafe { game.middle.push_unchecked(card) };
+            }
+            other.truncate(kept);
+        }
+
+        if let Some(card) = game.middle.pop() {
+            // Safety: the card came out of this hand, so it has room for it
+            unsafe { player.push_front(card) };
+        }
+
+        game.penalty = penalty;
+        game.to_move = turn.player;
+
+        Some(turn)
+    }
+}
+
+impl From<RecordedGame> for Game {
+    fn from(recorded: RecordedGame) -> Self {
+        recorded.game
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Game, WORLD_RECORD_DECK};
+
+    fn state(game: &Game) -> String {
+        format!("{game:?} {:?}", game.to_move)
+    }
+
+    #[test]
+    fn undo_restores_every_position() {
+        let start = Game::from_string(WORLD_RECORD_DECK);
+        let mut recorded = start.clone().with_history();
+
+        let mut positions = vec![state(&start)];
+        while recorded.step().is_some() {
+            positions.push(state(recorded.game()));
+        }
+        assert_eq!(recorded.len() + 1, 8_344);
+
+        while let Some(position) = positions.pop() {
+            assert_eq!(state(recorded.game()), position);
+            recorded.undo();
+        }
+        assert!(recorded.is_empty());
+        assert!(recorded.undo().is_none());
+    }
+}
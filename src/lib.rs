@@ -1,18 +1,64 @@
 //! implementation of beggar my neighbour card game
+//!
+//! The core ([`Game`], [`Card`] and their parsing, packing and playing) only needs `core`,
+//! so it can be built with `default-features = false` for `no_std` targets.
+//! The `alloc` feature adds the games that need to grow, and `std` adds entropy-seeded games and searches.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+mod batch;
+#[cfg(feature = "capi")]
+pub mod capi;
 mod circlebuffer;
 mod cursorslice;
+mod dealbuilder;
+mod deals;
+mod deckspec;
+#[cfg(feature = "alloc")]
+mod history;
+#[cfg(feature = "alloc")]
+mod multiplayer;
+#[cfg(feature = "alloc")]
+pub mod safe;
+#[cfg(feature = "std")]
+mod search;
+#[cfg(feature = "suits")]
+pub mod suits;
 
+pub use batch::play_all;
+#[cfg(feature = "std")]
+pub use batch::{par_play, parse_decks, random_games, run_batch, win_rate, WinRates};
 use circlebuffer::CircularBuffer;
-use cursorslice::CursorSlice;
-use std::{
+use core::{
     fmt::{Debug, Display},
+    ops::RangeInclusive,
     ptr,
 };
+use cursorslice::CursorSlice;
+pub use dealbuilder::{DealBuilder, DealError, Deals};
+pub use deals::{all_deals, all_deals_from, DEAL_COUNT};
+#[cfg(feature = "alloc")]
+pub use deckspec::new_deck_spec;
+pub use deckspec::DeckSpec;
+#[cfg(feature = "alloc")]
+pub use history::RecordedGame;
+#[cfg(feature = "alloc")]
+pub use multiplayer::{MultiGame, MultiStats};
+#[cfg(feature = "std")]
+pub use search::{
+    available_threads, climb, estimate_length, search_for_length, search_stream, DeckTemplate,
+    LengthEstimate, LongestRecord, LongestSearch, LongestSearchHandle, Metric, RecordStream,
+    Search, SearchResult,
+};
 
 /// Card is an enum representing 5 different types of cards that are used in beggar my neighbour
 /// There are 4 of each (Ace, King, Queen, Jack) and 36 other cards
+///
+/// Cards are ordered by their penalty, from the Ace at the top down to an Other.
 #[repr(u8)]
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Card {
     /// Penalty card, play 4
     Ace = 4,
@@ -26,39 +72,284 @@ pub enum Card {
 }
 
 impl Card {
+    /// Every kind of card, from the highest penalty to none
+    pub const ALL: [Self; 5] = [Self::Ace, Self::King, Self::Queen, Self::Jack, Self::Other];
+
+    /// A card's value is its penalty, so this cast is free. A lookup table benches the same in
+    /// [`Game::play`], where it is only worth it for [`Rules`] (see [`Game::play_with_rules`]).
     #[inline(always)]
     const fn penalty(self) -> u8 {
         self as u8
     }
 
-    fn from_char(c: char) -> Self {
+    /// How many of this card are in a full deck
+    pub const fn deck_count(self) -> usize {
+        match self {
+            Self::Other => DECK_SIZE - 16,
+            _ => 4,
+        }
+    }
+
+    /// The card with the given value, the inverse of `card as u8`, or `None` if no card has that value
+    #[must_use]
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            4 => Some(Self::Ace),
+            3 => Some(Self::King),
+            2 => Some(Self::Queen),
+            1 => Some(Self::Jack),
+            0 => Some(Self::Other),
+            _ => None,
+        }
+    }
+
+    const fn from_char(c: char) -> Option<Self> {
         match c {
-            'A' => Self::Ace,
-            'K' => Self::King,
-            'Q' => Self::Queen,
-            'J' => Self::Jack,
-            '-' => Self::Other,
-            _ => panic!("invalid character in string"),
+            'A' => Some(Self::Ace),
+            'K' => Some(Self::King),
+            'Q' => Some(Self::Queen),
+            'J' => Some(Self::Jack),
+            '-' => Some(Self::Other),
+            _ => None,
         }
     }
 }
 
-impl Display for Card {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let s = match self {
-            Self::Ace => "A",
-            Self::King => "K",
-            Self::Queen => "Q",
-            Self::Jack => "J",
-            Self::Other => "-",
-        };
+/// Which cards are penalty cards and how many cards each makes the other player pay, for playing house rules.
+///
+/// The default is [`Rules::classic`]. Any of the four can be given any penalty, and a penalty of 0
+/// makes that card play like an Other. As every card below a Jack is an Other, variants that make
+/// one of those a penalty card (like tens) can't be played.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Rules {
+    pub ace: u8,
+    pub king: u8,
+    pub queen: u8,
+    pub jack: u8,
+    /// Which way round the middle pile goes under the hand of whoever collects it
+    pub pickup: PickupOrder,
+}
+
+/// The order a collected middle pile is added to the bottom of the collector's hand
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum PickupOrder {
+    /// The first card played to the pile is the first to be played again, as in [`Game::play`]
+    #[default]
+    Forward,
+    /// The pile is flipped over, so the last card played to it is the first to be played again
+    Reversed,
+}
+
+impl Rules {
+    /// The usual rules, where an Ace is paid with 4 cards, a King with 3, a Queen with 2 and a Jack with 1
+    #[must_use]
+    pub const fn classic() -> Self {
+        Self {
+            ace: Card::Ace.penalty(),
+            king: Card::King.penalty(),
+            queen: Card::Queen.penalty(),
+            jack: Card::Jack.penalty(),
+            pickup: PickupOrder::Forward,
+        }
+    }
+
+    /// The classic rules, but only face cards are penalty cards, so Aces play like Others
+    #[must_use]
+    pub const fn faces_only() -> Self {
+        Self {
+            ace: 0,
+            ..Self::classic()
+        }
+    }
+
+    /// How many cards `card` makes the other player pay under these rules, or 0 if it isn't a penalty card
+    #[inline(always)]
+    #[must_use]
+    pub const fn penalty(&self, card: Card) -> u8 {
+        match card {
+            Card::Ace => self.ace,
+            Card::King => self.king,
+            Card::Queen => self.queen,
+            Card::Jack => self.jack,
+            Card::Other => 0,
+        }
+    }
+
+    /// Every card's penalty, indexed by `card as usize`, so the game loop can look a penalty up
+    /// rather than matching on the card like [`Rules::penalty`] does.
+    const fn penalties(&self) -> [u8; Card::ALL.len()] {
+        let mut penalties = [0; Card::ALL.len()];
+        let mut i = 0;
+        while i < Card::ALL.len() {
+            penalties[Card::ALL[i] as usize] = self.penalty(Card::ALL[i]);
+            i += 1;
+        }
+
+        penalties
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+/// The error returned when converting a number that isn't the value of any [`Card`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InvalidCardValue(pub u8);
+
+impl Display for InvalidCardValue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} is not the value of a card", self.0)
+    }
+}
+
+impl core::error::Error for InvalidCardValue {}
+
+impl TryFrom<u8> for Card {
+    type Error = InvalidCardValue;
+
+    /// See [`Card::from_u8`]
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::from_u8(value).ok_or(InvalidCardValue(value))
+    }
+}
+
+impl Card {
+    /// The character for this card in the `p1/p2` notation
+    const fn as_char(self) -> char {
+        match self {
+            Self::Ace => 'A',
+            Self::King => 'K',
+            Self::Queen => 'Q',
+            Self::Jack => 'J',
+            Self::Other => '-',
+        }
+    }
+}
 
-        write!(f, "{s}")
+impl Display for Card {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.as_char())
     }
 }
 
 pub const DECK_SIZE: usize = 52;
 
+/// The size of a game packed by [`Game::to_packed`]
+pub const PACKED_SIZE: usize = 20;
+
+/// How many turns [`Game::play`] plays before giving up on a (possibly infinite) game
+pub const TURN_LIMIT: usize = 100_000;
+
+/// The deal of the longest known game, which lasts [`KNOWN_RECORD_TURNS`] turns
+pub const WORLD_RECORD_DECK: &str = "---AJ--Q---------QAKQJJ-QK/-----A----KJ-K--------A---";
+
+/// How many turns the [`WORLD_RECORD_DECK`] lasts
+pub const KNOWN_RECORD_TURNS: usize = WORLD_RECORD_STATS.turns;
+
+/// The stats from playing the [`WORLD_RECORD_DECK`]
+pub const WORLD_RECORD_STATS: GameStats = GameStats {
+    turns: 8344,
+    tricks: 1164,
+    pickups: 1163,
+    p1_tricks: 555,
+    p2_tricks: 608,
+    max_pile: 26,
+    longest_penalty_chain: 11,
+    cards_moved: 16677,
+    terminated: Termination::Win(Player::P2),
+};
+
+/// Whether a game lasting `turns` turns would beat the [`WORLD_RECORD_DECK`]
+#[must_use]
+pub const fn is_new_record(turns: usize) -> bool {
+    turns > KNOWN_RECORD_TURNS
+}
+
+/// An error produced when parsing a game from its `p1/p2` string notation
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// A character other than `AKQJ-` was found at the given byte offset
+    InvalidChar { found: char, offset: usize },
+    /// The string didn't split into two hands (and optionally the middle pile) on `/`
+    WrongSectionCount(usize),
+    /// The two hands together hold more cards than a deck,
+    /// or for a [`DeckTemplate`], any number of cards other than a deck
+    WrongDeckSize { p1: usize, p2: usize },
+    /// The hands and middle pile together hold more cards than a deck
+    TooManyCards(usize),
+    /// A packed game had a 3-bit code that isn't a card, at the given position
+    InvalidCode { code: u8, offset: usize },
+    /// A packed game wasn't [`PACKED_SIZE`] bytes long
+    WrongPackedSize(usize),
+    /// A [`DeckTemplate`] pinned more of a card than a deck has
+    InvalidDeck(InvalidDeck),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidChar { found, offset } => {
+                write!(f, "invalid character {found:?} at offset {offset}")
+            }
+            Self::WrongSectionCount(count) => {
+                write!(
+                    f,
+                    "expected p1/p2 or p1/p2/middle, found {count} section{s} separated by '/'",
+                    s = if *count == 1 { "" } else { "s" }
+                )
+            }
+            Self::TooManyCards(cards) => {
+                write!(
+                    f,
+                    "game has {cards} cards, more than the {DECK_SIZE} in a deck"
+                )
+            }
+            Self::WrongDeckSize { p1, p2 } => {
+                let relation = if p1 + p2 > DECK_SIZE { "more" } else { "fewer" };
+                write!(
+                    f,
+                    "hands have {p1} + {p2} cards, which is {relation} than the {DECK_SIZE} in a deck"
+                )
+            }
+            Self::InvalidCode { code, offset } => {
+                write!(f, "invalid card code {code} at position {offset}")
+            }
+            Self::WrongPackedSize(size) => {
+                write!(f, "expected {PACKED_SIZE} bytes, found {size}")
+            }
+            Self::InvalidDeck(invalid) => write!(f, "{invalid}"),
+        }
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// The error returned by [`Game::validate`] when a game isn't made from exactly one deck
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InvalidDeck {
+    /// The first card (in [`Card::ALL`] order) that had the wrong count
+    pub card: Card,
+    pub expected: usize,
+    pub found: usize,
+}
+
+impl Display for InvalidDeck {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "expected {expected} of {card:?}, found {found}",
+            expected = self.expected,
+            card = self.card,
+            found = self.found
+        )
+    }
+}
+
+impl core::error::Error for InvalidDeck {}
+
 pub fn new_deck() -> [Card; DECK_SIZE] {
     let mut deck = [Card::Other; DECK_SIZE];
 
@@ -75,217 +366,2533 @@ pub fn new_deck() -> [Card; DECK_SIZE] {
     deck
 }
 
-fn randomize_deck(deck: &mut [Card; DECK_SIZE]) {
-    for i in (1..deck.len()).rev() {
-        unsafe {
-            ptr::swap(
-                deck.get_unchecked_mut(i),
-                deck.get_unchecked_mut(fastrand::usize(0..=i)),
-            );
-        }
+/// Shuffles `deck` in place, with every ordering equally likely.
+///
+/// This is the shuffle [`Game::random`] deals from, so shuffling a [`new_deck`] gives the same
+/// distribution of games. See [`shuffle_deck_seeded`] for a reproducible shuffle.
+#[cfg(feature = "std")]
+pub fn shuffle_deck(deck: &mut [Card; DECK_SIZE]) {
+    randomize_deck(deck, &mut fastrand::Rng::new());
+}
+
+/// Shuffles `deck` in place like [`shuffle_deck`], but only depending on `seed` and the starting order.
+///
+/// Shuffling a [`new_deck`] gives the same deck that [`Game::random_seeded`] deals with the same seed.
+pub fn shuffle_deck_seeded(deck: &mut [Card; DECK_SIZE], seed: u64) {
+    randomize_deck(deck, &mut fastrand::Rng::with_seed(seed));
+}
+
+/// A Fisher-Yates shuffle of `deck` using `rng`
+fn randomize_deck(deck: &mut [Card; DECK_SIZE], rng: &mut fastrand::Rng) {
+    // Both cards are reached through one pointer, as taking a second `&mut` into the deck
+    // would invalidate the first while it's still in use
+    let cards = deck.as_mut_ptr();
+
+    for i in (1..DECK_SIZE).rev() {
+        // Safety: `i` is below `DECK_SIZE`, and the other index is at most `i`.
+        // `ptr::swap` allows both to be the same card.
+        unsafe { ptr::swap(cards.add(i), cards.add(rng.usize(0..=i))) };
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Winner {
     P1,
     P2,
     Infinite,
 }
 
-#[derive(Clone)]
+impl From<Player> for Winner {
+    fn from(player: Player) -> Self {
+        match player {
+            Player::P1 => Self::P1,
+            Player::P2 => Self::P2,
+        }
+    }
+}
+
+impl From<Termination> for Winner {
+    fn from(termination: Termination) -> Self {
+        match termination {
+            Termination::Win(player) => player.into(),
+            Termination::LimitReached => Self::Infinite,
+        }
+    }
+}
+
+/// Why a game stopped being played
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Termination {
+    /// The other player is down to their last card, so this player won
+    Win(Player),
+    /// The turn limit was hit before anyone won, so the game may be infinite
+    LimitReached,
+}
+
+/// One of the two players in a game
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Player {
+    P1,
+    P2,
+}
+
+impl Player {
+    /// The player sitting across the table
+    #[must_use]
+    pub const fn other(self) -> Self {
+        match self {
+            Self::P1 => Self::P2,
+            Self::P2 => Self::P1,
+        }
+    }
+}
+
+/// The 3-bit code between the hands in [`Game::to_packed`]
+const PACKED_SEPARATOR: u8 = 5;
+/// The 3-bit code filling the rest of [`Game::to_packed`]
+const PACKED_END: u8 = 7;
+/// How many 3-bit codes fit in [`PACKED_SIZE`] bytes
+const PACKED_CODES: usize = PACKED_SIZE * 8 / 3;
+
+/// A whole deck fits in a [`Hand`], so dealing one can't fail
+const HAND_FITS: &str = "a deck fits in a hand";
+
+/// The capacity of a [`Hand`], the smallest power of 2 that can hold a whole deck
+const HAND_CAPACITY: usize = 64;
+
+/// A player's hand
+type Hand = CircularBuffer<Card, HAND_CAPACITY>;
+
+// The unchecked pushes while playing rely on a hand being able to take every card in the game
+const _: () = assert!(HAND_CAPACITY >= DECK_SIZE);
+
+/// Games are equal (and hash the same) when every hand, the middle pile, the penalty
+/// and the player to move are the same, however they were reached.
+///
+/// A game never holds more than [`DECK_SIZE`] cards between its hands and the middle pile.
+/// Every way of building one checks this (see [`ParseError::TooManyCards`]), and playing only moves
+/// cards around, whatever the [`Rules`]. So neither the middle pile, which holds exactly a deck, nor a
+/// hand can overflow, which is what lets the game loop push cards without checking for room.
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct Game {
     /// Player 1's deck, as a queue (we add to the back and remove from the front)
-    p1: CircularBuffer<Card>,
+    p1: Hand,
     /// Player 2's deck, as a queue (we add to the back and remove from the front)
-    p2: CircularBuffer<Card>,
-    /// The middle pile, as a vec (we only ever add to it)
+    p2: Hand,
+    /// The middle pile, as a vec (we only ever add to it). This can hold every card in the game.
     middle: CursorSlice<Card, DECK_SIZE>,
     penalty: u8,
+    /// The player who plays the next card
+    to_move: Player,
 }
 
-#[derive(Debug, Copy, Clone)]
+/// What happened over a played game.
+///
+/// Turns are counted the way game length records are, as the number of cards played including the
+/// loser's final card. That card is never actually played, since the game is decided as soon as the
+/// player to move is down to it, so `turns` is one more than the number of [`Game::step`]s and
+/// every game is at least 1 turn long (`"A/-"`, where p1 starts on their last card, is exactly 1).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct GameStats {
+    /// How many cards were played, including the loser's final card
     pub turns: usize,
+    /// How many tricks were started, which is how many penalty cards were played while no penalty
+    /// was being paid. A penalty card played to pay another penalty carries on the same trick,
+    /// and the final card isn't counted even if it would have been a penalty card.
     pub tricks: usize,
+    /// How many times the middle pile was collected, which is `p1_tricks + p2_tricks`.
+    ///
+    /// This differs from `tricks`, which counts penalties started rather than penalties won.
+    pub pickups: usize,
+    /// How many times player 1 won a trick and collected the middle pile
+    pub p1_tricks: usize,
+    /// How many times player 2 won a trick and collected the middle pile
+    pub p2_tricks: usize,
+    /// The most cards the middle pile held at once
+    pub max_pile: usize,
+    /// The most penalty cards played in a single trick, counting each that answered the one before it.
+    /// This is at most 16, when every penalty card in the deck is played before the pile is collected.
+    ///
+    /// It's a `usize` like the other counts, rather than a `u8`, as a [`SafeGame`](safe::SafeGame)
+    /// can play a deck with any number of penalty cards.
+    pub longest_penalty_chain: usize,
+    /// How many times a card moved: every card played to the middle pile, plus every card collected from it.
+    /// This is a measure of the work done in a game that, unlike `turns`, grows with the size of the tricks.
+    /// It can outgrow `turns` many times over, so it stops at `usize::MAX` rather than overflowing.
+    pub cards_moved: usize,
+    /// Whether the game was won or cut off by the turn limit
+    pub terminated: Termination,
 }
 
-impl Game {
-    #[must_use]
-    pub fn random(deck: &mut [Card; DECK_SIZE]) -> Self {
-        const MID: usize = DECK_SIZE / 2;
-
-        // We can just shuffle the original deck since it will be re-shuffled every time
-        randomize_deck(deck);
-
+impl GameStats {
+    /// The stats of a game that is yet to be played, with `pile` cards already in the middle
+    const fn start(pile: usize) -> Self {
         Self {
-            p1: unsafe { CircularBuffer::from_memory(deck.as_ptr(), MID) },
-            p2: unsafe { CircularBuffer::from_memory(deck.as_ptr().add(MID), MID) },
-            middle: CursorSlice::new(),
-            penalty: 0,
+            // like in `play`, the loser's final card counts as a turn
+            turns: 1,
+            tricks: 0,
+            pickups: 0,
+            p1_tricks: 0,
+            p2_tricks: 0,
+            max_pile: pile,
+            longest_penalty_chain: 0,
+            cards_moved: 0,
+            terminated: Termination::LimitReached,
         }
     }
 
-    pub fn from_string(string: &str) -> Self {
-        let split_string: Vec<&str> = string.split('/').collect();
+    /// How many penalty cards are in `middle`, which all belong to the trick being played.
+    /// This starts the running count [`GameStats::tally`] keeps, like the one in [`Game::play`].
+    fn penalty_chain(middle: &[Card]) -> usize {
+        middle.iter().filter(|&&card| card != Card::Other).count()
+    }
 
-        // Replace dual bound checks with a single check
-        assert!(split_string.len() == 2);
+    /// Counts a turn returned by [`Game::step`], given the penalty and pile size from before it,
+    /// and keeping `chain` as the number of penalty cards in the trick being played
+    fn tally(&mut self, turn: &Turn, penalty: u8, pile: usize, chain: &mut usize) {
+        self.turns += 1;
+        self.cards_moved = self.cards_moved.saturating_add(1);
 
-        let p1 = split_string[0].chars().map(Card::from_char).collect();
-        let p2 = split_string[1].chars().map(Card::from_char).collect();
+        if turn.penalty {
+            if penalty == 0 {
+                self.tricks += 1;
+            }
 
-        Self {
-            p1,
-            p2,
-            middle: CursorSlice::new(),
-            penalty: 0,
+            *chain += 1;
+            self.longest_penalty_chain = self.longest_penalty_chain.max(*chain);
         }
+
+        if turn.collected {
+            self.pickups += 1;
+            *chain = 0;
+            // the card that failed to pay the penalty is collected too
+            self.cards_moved = self.cards_moved.saturating_add(pile + 1);
+            match turn.player.other() {
+                Player::P1 => self.p1_tricks += 1,
+                Player::P2 => self.p2_tricks += 1,
+            }
+        }
+
+        self.max_pile = self.max_pile.max(pile + 1);
     }
+}
 
-    #[must_use]
-    pub const fn winner(&self) -> Winner {
-        if self.p1.len() == 1 {
-            Winner::P2
-        } else if self.p2.len() == 1 {
-            Winner::P1
-        } else {
-            Winner::Infinite
+/// A one line summary, like `8344 turns / 1164 tricks`.
+/// The alternate form (`{:#}`) lists every statistic on its own line.
+impl Display for GameStats {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if !f.alternate() {
+            return write!(f, "{} turns / {} tricks", self.turns, self.tricks);
+        }
+
+        writeln!(f, "turns: {}", self.turns)?;
+        writeln!(f, "tricks: {}", self.tricks)?;
+        writeln!(
+            f,
+            "pickups: {} (p1: {}, p2: {})",
+            self.pickups, self.p1_tricks, self.p2_tricks
+        )?;
+        writeln!(f, "max pile: {}", self.max_pile)?;
+        writeln!(f, "longest penalty chain: {}", self.longest_penalty_chain)?;
+        writeln!(f, "cards moved: {}", self.cards_moved)?;
+
+        match self.terminated {
+            Termination::Win(player) => write!(f, "winner: {player:?}"),
+            Termination::LimitReached => write!(f, "winner: none, the turn limit was reached"),
         }
     }
+}
 
-    /// Plays out a game of beggar my neighbour, returning how many steps it took
-    pub fn play(&mut self) -> GameStats {
-        // We can't produce a game thats less than 1 turn long
-        // so we can skip some arithmetic
-        let mut turns = 1;
-        let mut tricks = 0;
+/// The outcome of a game along with its stats, as returned by [`Game::play_result`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct GameResult {
+    /// Who won, or [`Winner::Infinite`] if the game was cut off by the turn limit
+    pub winner: Winner,
+    pub stats: GameStats,
+}
 
-        let mut current_player = &mut self.p1;
-        let mut other_player = &mut self.p2;
+/// How many turns [`Game::play_diagnostic`] keeps playing past its cap to see where a game is heading
+pub const DIAGNOSIS_WINDOW: usize = 10_000;
 
-        loop {
-            unsafe {
-                // We can return early (len = 1) because regardless of the card played, the game is over
-                if (*current_player).len() == 1 {
-                    break GameStats { turns, tricks };
-                }
+/// Which way player 1's hand was heading over a [`DIAGNOSIS_WINDOW`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Trend {
+    /// Player 1 mostly gained cards, so player 2 looks like they'll run out eventually
+    Growing,
+    /// Player 1 mostly lost cards, so they look like they'll run out eventually
+    Shrinking,
+    /// The hands rose and fell without getting anywhere, so the game may well be a loop
+    Oscillating,
+}
 
-                // we can safely pop here because we know the player has cards (otherwise the game would be over)
-                // *unless current_player.len() == 0, which is impossible we only remove 1 card at a time
-                let card = (*current_player).pop_unchecked();
-                self.middle.push_unchecked(card);
-                turns += 1;
+/// How player 1's hand size moved over the turns played past the cap, as recorded by [`Game::play_diagnostic`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct HandTrend {
+    /// How many cards player 1 held once the cap was reached
+    pub first: usize,
+    /// How many cards player 1 held at the end of the window
+    pub last: usize,
+    /// The fewest cards player 1 held during the window
+    pub min: usize,
+    /// The most cards player 1 held during the window
+    pub max: usize,
+    /// Growing or shrinking if player 1's average hand over the last quarter of the window was bigger
+    /// or smaller than over the first quarter by at least a quarter of the range, otherwise oscillating
+    pub trend: Trend,
+}
 
-                if card == Card::Other {
-                    match self.penalty {
-                        0 => std::mem::swap(&mut current_player, &mut other_player),
-                        // If the penalty is 1 and the player hasn't played a penalty card, the other player takes all the cards
-                        // from the middle and adds them to the beginning of their deck
-                        1 => {
-                            std::mem::swap(&mut current_player, &mut other_player);
+/// A game played by [`Game::play_diagnostic`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Diagnosis {
+    /// The stats of every turn played, including any past the cap
+    pub stats: GameStats,
+    /// Where the hands were heading, if the game was still going a whole [`DIAGNOSIS_WINDOW`] past the cap
+    /// (so it is presumed infinite). This is `None` if the game was won.
+    pub trend: Option<HandTrend>,
+}
 
-                            (*current_player).push_slice(self.middle.slice());
-                            self.middle.clear();
+/// The state of a game just after a trick was won, as recorded by [`Game::play_tracing`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TrickSnapshot {
+    /// How many cards had been played, including the one that failed to pay the penalty
+    pub turn: usize,
+    /// The player who won the trick and collected the middle pile
+    pub collector: Player,
+    /// How many cards were collected
+    pub pile: usize,
+    /// How many cards player 1 held after the pile was collected
+    pub p1_cards: usize,
+    /// How many cards player 2 held after the pile was collected
+    pub p2_cards: usize,
+}
 
-                            self.penalty = 0;
-                            if turns > 100_000 {
-                                break GameStats { turns, tricks };
-                            }
-                        }
-                        _ => self.penalty -= 1,
-                    };
-                } else {
-                    // regardless if the game currently has penalty,
-                    // if the player plays a penalty card,
-                    // the penalty is set and the other player must play
-                    if self.penalty == 0 {
-                        tricks += 1;
-                    }
-                    self.penalty = card.penalty();
-                    std::mem::swap(&mut current_player, &mut other_player);
-                }
-            }
+/// How far ahead player 1 was on average over a game traced by [`Game::play_tracing`],
+/// in cards: positive if player 1 generally held more cards than player 2, negative if they held fewer.
+///
+/// Each snapshot's difference in hand sizes counts for the turns until the next snapshot,
+/// so the average runs from the first trick won to the last. This also covers games that hit
+/// [`TURN_LIMIT`], which just stop at their last snapshot. With a single snapshot, that snapshot's
+/// difference is returned, and with none (so no trick was ever won) this is 0.
+#[must_use]
+pub fn hand_advantage(snapshots: &[TrickSnapshot]) -> f64 {
+    let difference = |snapshot: &TrickSnapshot| snapshot.p1_cards as f64 - snapshot.p2_cards as f64;
+
+    match snapshots {
+        [] => 0.0,
+        [snapshot] => difference(snapshot),
+        [first, .., last] => {
+            let area: f64 = snapshots
+                .windows(2)
+                .map(|pair| difference(&pair[0]) * (pair[1].turn - pair[0].turn) as f64)
+                .sum();
+
+            area / (last.turn - first.turn) as f64
         }
     }
 }
 
-impl Display for Game {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut s = String::new();
-
-        s.push_str("p1: ");
-        for card in self.p1.iter() {
-            s.push_str(&format!("{card}"));
-        }
+/// A single card play, as returned by [`Game::step`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Turn {
+    /// The player who played the card
+    pub player: Player,
+    pub card: Card,
+    /// Whether the card was a penalty card, which the other player now has to pay
+    pub penalty: bool,
+    /// Whether this card failed to pay a penalty, so the other player collected the middle pile
+    pub collected: bool,
+    /// The size of the middle pile after the card was played (0 if it was collected)
+    pub pile: usize,
+}
 
-        s.push_str("\np2: ");
-        for card in self.p2.iter() {
-            s.push_str(&format!("{card}"));
-        }
+/// An iterator that plays a game one card at a time, as returned by [`Game::turns`]
+#[derive(Debug)]
+pub struct Turns<'a> {
+    game: &'a mut Game,
+}
 
-        if !self.middle.is_empty() {
-            s.push_str("\nmiddle: ");
-            for card in self.middle.iter() {
-                s.push_str(&format!("{card}"));
-            }
-        }
+impl Iterator for Turns<'_> {
+    type Item = Turn;
 
-        write!(f, "{s}")
+    fn next(&mut self) -> Option<Turn> {
+        self.game.step()
     }
 }
 
-impl Debug for Game {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut s = String::with_capacity(DECK_SIZE + 4);
+/// Plays copies of two games side by side, returning the first turn (counting from 1, like
+/// [`TrickSnapshot::turn`]) on which they played out differently, or `None` if every turn matched.
+///
+/// A turn differs if a different player or card was played, a different pile was collected, or only
+/// one of the games was over. As a game's turns only depend on its position, this checks that two ways
+/// of reaching or dealing a game (e.g. before and after an optimization) play it the same way, and shows
+/// how long two similar deals (like ones a single swap apart) keep playing alike. Stops after [`TURN_LIMIT`] turns.
+#[must_use]
+pub fn diverge_turn(a: &Game, b: &Game) -> Option<usize> {
+    let (mut a, mut b) = (a.clone(), b.clone());
 
-        for card in self.p1.iter() {
-            s.push_str(&format!("{card}"));
+    for turn in 1..=TURN_LIMIT {
+        match (a.step(), b.step()) {
+            (None, None) => return None,
+            (a, b) if a != b => return Some(turn),
+            _ => {}
         }
+    }
 
-        s.push('/');
+    None
+}
 
-        for card in self.p2.iter() {
-            s.push_str(&format!("{card}"));
+/// How many of each card a hand holds, as returned by [`Game::hand_counts`]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct HandCounts {
+    pub aces: usize,
+    pub kings: usize,
+    pub queens: usize,
+    pub jacks: usize,
+    pub others: usize,
+}
+
+impl HandCounts {
+    fn of(cards: &[Card]) -> Self {
+        let mut counts = Self::default();
+
+        for card in cards {
+            match card {
+                Card::Ace => counts.aces += 1,
+                Card::King => counts.kings += 1,
+                Card::Queen => counts.queens += 1,
+                Card::Jack => counts.jacks += 1,
+                Card::Other => counts.others += 1,
+            }
         }
 
-        if self.penalty > 0 {
-            s.push_str(&format!("+{}", self.penalty));
+        counts
+    }
+
+    /// How many of `card` the hand holds
+    #[must_use]
+    pub const fn count(&self, card: Card) -> usize {
+        match card {
+            Card::Ace => self.aces,
+            Card::King => self.kings,
+            Card::Queen => self.queens,
+            Card::Jack => self.jacks,
+            Card::Other => self.others,
         }
+    }
 
-        write!(f, "{s}")
+    /// How many cards the hand holds
+    #[must_use]
+    pub const fn total(&self) -> usize {
+        self.aces + self.kings + self.queens + self.jacks + self.others
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::Game;
+impl Game {
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn random(deck: &mut [Card; DECK_SIZE]) -> Self {
+        Self::random_with_rng(deck, &mut fastrand::Rng::new())
+    }
 
-    fn assert_game(game: &str, turns: usize, tricks: usize) {
-        let game = &mut Game::from_string(game);
+    /// Deals a random game using `rng`, for when there's no entropy source to seed [`Game::random`] from.
+    ///
+    /// Like [`Game::random`], this shuffles `deck` in place without resetting it first.
+    #[must_use]
+    pub fn random_with_rng(deck: &mut [Card; DECK_SIZE], rng: &mut fastrand::Rng) -> Self {
+        // We can just shuffle the original deck since it will be re-shuffled every time
+        randomize_deck(deck, rng);
 
-        let stats = game.play();
+        Self::deal(deck)
+    }
 
-        assert_eq!(stats.turns, turns);
-        assert_eq!(stats.tricks, tricks);
+    /// Deals a random game shuffled by any random number generator, like one from the `rand` crate,
+    /// without this crate depending on it.
+    ///
+    /// `pick` is called with each range of indices `0..=i` the shuffle needs, and must return an index
+    /// chosen uniformly from it (e.g. `|range| rng.gen_range(range)`). Like [`Game::random`], this shuffles
+    /// `deck` in place without resetting it first, and gives the same game as [`Game::random_with_rng`]
+    /// when `pick` draws from a [`fastrand::Rng`] in the same way.
+    ///
+    /// Panics if `pick` returns an index outside the deck.
+    #[must_use]
+    pub fn deal_from_rng(
+        deck: &mut [Card; DECK_SIZE],
+        mut pick: impl FnMut(RangeInclusive<usize>) -> usize,
+    ) -> Self {
+        // The indices come from outside, so this shuffle is bounds checked, unlike `randomize_deck`
+        for i in (1..DECK_SIZE).rev() {
+            deck.swap(i, pick(0..=i));
+        }
+
+        Self::deal(deck)
     }
 
-    #[test]
-    fn world_record_games() {
-        assert_game(
-            "---AJ--Q---------QAKQJJ-QK/-----A----KJ-K--------A---",
-            8_344,
-            1_164,
-        );
-        assert_game(
-            "K-KK----K-A-----JAA--Q--J-/---Q---Q-J-----J------AQ--",
-            7_157,
-            1_007,
+    /// Deals a random game which only depends on `seed`, so the same seed always gives the same game.
+    ///
+    /// Unlike [`Game::random`], `deck` is reset to [`new_deck`] before shuffling, so it is only used as scratch space.
+    #[must_use]
+    pub fn random_seeded(deck: &mut [Card; DECK_SIZE], seed: u64) -> Self {
+        *deck = new_deck();
+        shuffle_deck_seeded(deck, seed);
+
+        Self::deal(deck)
+    }
+
+    /// Deals a random game where p1 gets the first `p1_len` cards and p2 gets the rest,
+    /// for house rules that don't deal evenly.
+    ///
+    /// Panics unless both players get at least one card (`0 < p1_len < 52`).
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn random_split(deck: &mut [Card; DECK_SIZE], p1_len: usize) -> Self {
+        assert!(
+            (1..DECK_SIZE).contains(&p1_len),
+            "both players must be dealt at least one card"
         );
-        assert_game(
-            "A-QK------Q----KA-----J---/-JAK----A--Q----J---QJ--K-",
-            6_913,
-            960,
-        )
+
+        shuffle_deck(deck);
+
+        Self::deal_split(deck, p1_len)
+    }
+
+    /// Splits a deck down the middle between the two players
+    fn deal(deck: &[Card; DECK_SIZE]) -> Self {
+        Self::deal_split(deck, DECK_SIZE / 2)
+    }
+
+    /// Splits a deck between the two players, with the first `p1_len` cards going to p1
+    fn deal_split(deck: &[Card; DECK_SIZE], p1_len: usize) -> Self {
+        let (p1, p2) = deck.split_at(p1_len);
+
+        Self {
+            p1: CircularBuffer::from_slice(p1).expect(HAND_FITS),
+            p2: CircularBuffer::from_slice(p2).expect(HAND_FITS),
+            middle: CursorSlice::new(),
+            penalty: 0,
+            to_move: Player::P1,
+        }
+    }
+
+    /// Turns this game back into a fresh deal of `deck`, the same game as dealing it from scratch.
+    ///
+    /// This rewrites the hands in place, so replaying many decks through one `Game` avoids building
+    /// (or cloning) a whole new game for each of them.
+    pub fn reset_to_deck(&mut self, deck: &[Card; DECK_SIZE]) {
+        const MID: usize = DECK_SIZE / 2;
+
+        let (p1, p2) = deck.split_at(MID);
+
+        self.p1.refill(p1).expect(HAND_FITS);
+        self.p2.refill(p2).expect(HAND_FITS);
+        self.middle.clear();
+        self.penalty = 0;
+        self.to_move = Player::P1;
+    }
+
+    /// Parses a game from its `p1/p2` notation (e.g. `"A-K/-QJ"`), panicking on invalid input.
+    ///
+    /// See [`Game::try_from_string`] for a non-panicking version.
+    pub fn from_string(string: &str) -> Self {
+        Self::try_from_string(string).unwrap()
+    }
+
+    /// Parses a game from its `p1/p2` notation (e.g. `"A-K/-QJ"`).
+    ///
+    /// A game part way through can also be parsed from the full notation its `Debug` output uses,
+    /// `p1/p2/middle`, followed by `+n` while a penalty of `n` is being paid and `@2` if it's
+    /// player 2's turn (e.g. `"A-/-QJ/K-+2@2"`). The middle pile is written from the first card played to the last.
+    ///
+    /// To read decks copied from elsewhere, whitespace is skipped and cards can be lowercase
+    /// (so `"a k -\n/ q j"` is `"AK-/QJ"`). See [`Game::try_from_string_strict`] to only accept the exact notation.
+    pub fn try_from_string(string: &str) -> Result<Self, ParseError> {
+        Self::parse(string, false)
+    }
+
+    /// Parses a game like [`Game::try_from_string`], but rejects whitespace and lowercase cards,
+    /// so only the exact notation `Debug` writes is accepted.
+    pub fn try_from_string_strict(string: &str) -> Result<Self, ParseError> {
+        Self::parse(string, true)
+    }
+
+    fn parse(string: &str, strict: bool) -> Result<Self, ParseError> {
+        let card = |c: char| {
+            if strict {
+                Card::from_char(c)
+            } else {
+                Card::from_char(c.to_ascii_uppercase())
+            }
+        };
+        let cards_in = |section: &str| section.chars().filter_map(card).count();
+
+        let trimmed = if strict { string } else { string.trim_end() };
+
+        let (cards, to_move) = match trimmed.strip_suffix("@2") {
+            Some(cards) => (cards, Player::P2),
+            None => (trimmed, Player::P1),
+        };
+
+        let (cards, penalty) = match cards.as_bytes() {
+            [.., b'+', digit @ b'1'..=b'4'] => (&cards[..cards.len() - 2], digit - b'0'),
+            _ => (cards, 0),
+        };
+
+        let mut sections = cards.split('/');
+
+        let (Some(p1), Some(p2), middle, None) = (
+            sections.next(),
+            sections.next(),
+            sections.next(),
+            sections.next(),
+        ) else {
+            return Err(ParseError::WrongSectionCount(cards.split('/').count()));
+        };
+        let middle = middle.unwrap_or_default();
+
+        // the suffixes are at the end, so offsets into `cards` are offsets into `string`
+        if let Some((offset, found)) = cards
+            .char_indices()
+            .find(|&(_, c)| c != '/' && card(c).is_none() && (strict || !c.is_whitespace()))
+        {
+            return Err(ParseError::InvalidChar { found, offset });
+        }
+
+        // The middle pile can hold at most a deck, so anything larger can't be played.
+        let (p1_len, p2_len) = (cards_in(p1), cards_in(p2));
+        if p1_len + p2_len > DECK_SIZE {
+            return Err(ParseError::WrongDeckSize {
+                p1: p1_len,
+                p2: p2_len,
+            });
+        }
+
+        let total = p1_len + p2_len + cards_in(middle);
+        if total > DECK_SIZE {
+            return Err(ParseError::TooManyCards(total));
+        }
+
+        let mut pile = CursorSlice::new();
+        for card in middle.chars().filter_map(card) {
+            // Safety: the middle pile holds a whole deck, and there are no more cards than that
+            unsafe { pile.push_unchecked(card) };
+        }
+
+        Ok(Self {
+            p1: p1.chars().filter_map(card).collect(),
+            p2: p2.chars().filter_map(card).collect(),
+            middle: pile,
+            penalty,
+            to_move,
+        })
+    }
+
+    /// Builds a fresh game from each player's hand, from the next card they'll play to the last.
+    ///
+    /// Unlike [`Game::try_from_string`], the hands must make up exactly one deck between them (see [`Game::validate`]).
+    pub fn from_hands(p1: &[Card], p2: &[Card]) -> Result<Self, ParseError> {
+        if p1.len() + p2.len() != DECK_SIZE {
+            return Err(ParseError::WrongDeckSize {
+                p1: p1.len(),
+                p2: p2.len(),
+            });
+        }
+
+        let game = Self {
+            p1: CircularBuffer::from_slice(p1).expect(HAND_FITS),
+            p2: CircularBuffer::from_slice(p2).expect(HAND_FITS),
+            middle: CursorSlice::new(),
+            penalty: 0,
+            to_move: Player::P1,
+        };
+
+        game.validate().map_err(ParseError::InvalidDeck)?;
+        Ok(game)
+    }
+
+    /// Checks that the game holds exactly one deck's worth of each card
+    /// (4 Aces, 4 Kings, 4 Queens, 4 Jacks and 36 Others) across both hands and the middle pile.
+    ///
+    /// As every kind of card is checked, this also guarantees the game holds [`DECK_SIZE`] cards.
+    pub fn validate(&self) -> Result<(), InvalidDeck> {
+        let mut counts = [0; Card::ALL.len()];
+
+        for card in self
+            .p1
+            .iter()
+            .chain(self.p2.iter())
+            .chain(self.middle.iter())
+        {
+            counts[*card as usize] += 1;
+        }
+
+        for card in Card::ALL {
+            let found = counts[card as usize];
+            if found != card.deck_count() {
+                return Err(InvalidDeck {
+                    card,
+                    expected: card.deck_count(),
+                    found,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    #[must_use]
+    /// A stable 64-bit hash of both hands (p1 then p2), for deduplicating starting decks.
+    ///
+    /// The middle pile and penalty are ignored, as they are always empty at the start of a game.
+    /// This is FNV-1a over the card values, so it's the same across runs, versions and platforms.
+    pub fn deck_id(&self) -> u64 {
+        const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        // Separate the hands with a byte no card uses, so moving a card between hands changes the id
+        let bytes = self
+            .p1
+            .iter()
+            .map(|&card| card as u8)
+            .chain([u8::MAX])
+            .chain(self.p2.iter().map(|&card| card as u8));
+
+        bytes.fold(OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+        })
+    }
+
+    /// Packs both hands into [`PACKED_SIZE`] bytes, less than half the size of the string notation.
+    ///
+    /// Each card is stored as its 3-bit value (`card as u8`), lowest bits first, with p1's cards,
+    /// then a 3-bit separator (5), then p2's cards. Unused space is filled with 3-bit end markers (7).
+    /// Like [`Game::deck_id`], this only stores the hands, not the middle pile or penalty.
+    pub fn to_packed(&self) -> [u8; PACKED_SIZE] {
+        let mut packed = [0; PACKED_SIZE];
+
+        let codes = self
+            .p1
+            .iter()
+            .map(|&card| card as u8)
+            .chain([PACKED_SEPARATOR])
+            .chain(self.p2.iter().map(|&card| card as u8))
+            .chain(core::iter::repeat(PACKED_END));
+
+        // 52 cards and a separator take 159 of the 160 bits
+        for (i, code) in codes.take(PACKED_CODES).enumerate() {
+            let (byte, shift) = ((i * 3) / 8, (i * 3) % 8);
+            let window = u16::from(code) << shift;
+
+            packed[byte] |= window as u8;
+            if shift > 5 {
+                packed[byte + 1] |= (window >> 8) as u8;
+            }
+        }
+
+        packed
+    }
+
+    /// Unpacks a game from [`Game::to_packed`]
+    pub fn from_packed(bytes: &[u8]) -> Result<Self, ParseError> {
+        if bytes.len() != PACKED_SIZE {
+            return Err(ParseError::WrongPackedSize(bytes.len()));
+        }
+
+        let mut hands = [Hand::new(), Hand::new()];
+        let mut hand = 0;
+
+        for i in 0..PACKED_CODES {
+            let (byte, shift) = ((i * 3) / 8, (i * 3) % 8);
+            let mut window = u16::from(bytes[byte]);
+            if shift > 5 {
+                window |= u16::from(bytes[byte + 1]) << 8;
+            }
+            let code = ((window >> shift) & 0b111) as u8;
+
+            match (code, Card::from_u8(code)) {
+                (PACKED_END, _) => break,
+                (PACKED_SEPARATOR, _) if hand == 0 => hand = 1,
+                (_, Some(card)) => {
+                    // there are only enough codes to hold a deck, so this can't overflow
+                    hands[hand]
+                        .try_push(card)
+                        .map_err(|_| ParseError::InvalidCode { code, offset: i })?;
+                }
+                _ => return Err(ParseError::InvalidCode { code, offset: i }),
+            }
+        }
+
+        if hand == 0 {
+            return Err(ParseError::WrongSectionCount(1));
+        }
+
+        let [p1, p2] = hands;
+        Ok(Self {
+            p1,
+            p2,
+            middle: CursorSlice::new(),
+            penalty: 0,
+            to_move: Player::P1,
+        })
+    }
+
+    /// Every game made by cutting each player's hand, covering all `p1 × p2` combinations of cuts.
+    ///
+    /// The first game yielded is this game itself (neither hand cut).
+    /// Cut games generally don't play the same as the original, which makes this a good
+    /// neighbourhood to search around a known long game.
+    pub fn rotations(&self) -> impl Iterator<Item = Self> {
+        let game = self.clone();
+        let p2_len = game.p2.len();
+
+        (0..game.p1.len()).flat_map(move |p1_cut| {
+            let game = game.clone();
+
+            (0..p2_len).map(move |p2_cut| {
+                let mut rotated = game.clone();
+                rotated.p1.rotate(p1_cut);
+                rotated.p2.rotate(p2_cut);
+                rotated
+            })
+        })
+    }
+
+    /// The same game with the players' seats swapped, so p2 holds p1's hand and plays whenever p1 would have.
+    ///
+    /// This plays exactly the same way, with the winner flipped.
+    #[must_use]
+    pub fn swapped(&self) -> Self {
+        Self {
+            p1: self.p2,
+            p2: self.p1,
+            middle: self.middle,
+            penalty: self.penalty,
+            to_move: self.to_move.other(),
+        }
+    }
+
+    /// A representative of every game equal to this one up to cutting each hand (see [`Game::rotations`])
+    /// and swapping the players (see [`Game::swapped`]), for deduplicating games in a search.
+    ///
+    /// Of all of those games, this picks the one with the smallest `p1/p2` notation (the `Debug` string),
+    /// breaking ties by having p1 move first. Only the swap preserves how a game plays, flipping the winner;
+    /// cuts generally play completely differently, so games with the same canonical form can have
+    /// different lengths. Use [`Game::deck_id`] to tell those apart.
+    #[must_use]
+    pub fn canonical(&self) -> Self {
+        self.rotations()
+            .chain(self.swapped().rotations())
+            .min_by(|a, b| {
+                a.notation()
+                    .cmp(b.notation())
+                    .then((a.to_move as u8).cmp(&(b.to_move as u8)))
+            })
+            .unwrap_or_else(|| self.clone())
+    }
+
+    /// The characters of the `Debug` notation, without building a string
+    fn notation(&self) -> impl Iterator<Item = char> + '_ {
+        let penalty = (self.penalty > 0).then(|| ['+', char::from(b'0' + self.penalty)]);
+
+        self.p1
+            .iter()
+            .map(|card| card.as_char())
+            .chain(['/'])
+            .chain(self.p2.iter().map(|card| card.as_char()))
+            .chain(penalty.into_iter().flatten())
+    }
+
+    /// Player 1's cards, from the next one they'll play to the last
+    pub const fn p1_cards(&self) -> &[Card] {
+        self.p1.slice()
+    }
+
+    /// Player 2's cards, from the next one they'll play to the last
+    pub const fn p2_cards(&self) -> &[Card] {
+        self.p2.slice()
+    }
+
+    /// Player 1's cards as their values (`card as u8`, so 4 for an Ace down to 0 for an Other),
+    /// in the same order as [`Game::p1_cards`]. This mapping is stable, and [`Card::from_u8`] reverses it.
+    pub fn p1_codes(&self) -> impl Iterator<Item = u8> + '_ {
+        self.p1.iter().map(|&card| card as u8)
+    }
+
+    /// Player 2's cards as their values, like [`Game::p1_codes`]
+    pub fn p2_codes(&self) -> impl Iterator<Item = u8> + '_ {
+        self.p2.iter().map(|&card| card as u8)
+    }
+
+    /// A copy of `player`'s hand sorted by rank, from their Aces down to their Others
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn sorted_hand(&self, player: Player) -> alloc::vec::Vec<Card> {
+        let hand = match player {
+            Player::P1 => self.p1_cards(),
+            Player::P2 => self.p2_cards(),
+        };
+
+        let mut sorted = hand.to_vec();
+        sorted.sort_unstable_by(|a, b| b.cmp(a));
+        sorted
+    }
+
+    /// How many of each card p1 and p2 currently hold
+    #[must_use]
+    pub fn hand_counts(&self) -> (HandCounts, HandCounts) {
+        (
+            HandCounts::of(self.p1_cards()),
+            HandCounts::of(self.p2_cards()),
+        )
+    }
+
+    /// A cheap guess at how long the game will last, for ranking a batch of deals before playing them.
+    ///
+    /// This is twice the number of penalty cards held by whichever player has fewer, so `16` for a deal
+    /// where they're split evenly and `0` where one player has them all. Over random deals that has a
+    /// correlation of about 0.25 with the length of the game, so it only tells likely long games apart
+    /// on average and says nothing certain about any one game.
+    #[must_use]
+    pub fn quick_estimate(&self) -> usize {
+        let (p1, p2) = self.hand_counts();
+        let penalty_cards = |hand: HandCounts| hand.total() - hand.others;
+
+        2 * penalty_cards(p1).min(penalty_cards(p2))
+    }
+
+    /// The number of cards in the middle pile
+    pub const fn middle_len(&self) -> usize {
+        self.middle.len()
+    }
+
+    /// The cards in the middle pile, from the bottom (played first) to the top (played last)
+    pub const fn pile_cards(&self) -> &[Card] {
+        self.middle.slice()
+    }
+
+    /// The winner of the game, if it is over.
+    ///
+    /// The game is over once the player to move is down to their last card, which is when
+    /// [`Game::play`] reports [`Termination::Win`]. A player with no cards at all (which only a
+    /// hand-written game like `"/AKQJ"` can have) has already lost, even if it isn't their turn.
+    /// Otherwise this is [`Winner::Infinite`].
+    pub const fn winner(&self) -> Winner {
+        let (current_player, other_player) = match self.to_move {
+            Player::P1 => (&self.p1, &self.p2),
+            Player::P2 => (&self.p2, &self.p1),
+        };
+
+        let (current, other) = match self.to_move {
+            Player::P1 => (Winner::P1, Winner::P2),
+            Player::P2 => (Winner::P2, Winner::P1),
+        };
+
+        if current_player.len() <= 1 {
+            other
+        } else if other_player.len() == 0 {
+            current
+        } else {
+            Winner::Infinite
+        }
+    }
+
+    /// The player who plays the next card
+    #[must_use]
+    pub const fn to_move(&self) -> Player {
+        self.to_move
+    }
+
+    /// How many more cards the player to move must play to pay off the last face card or Ace,
+    /// or 0 if no penalty is being paid. This is the `+n` suffix of the `Debug` notation.
+    #[must_use]
+    pub const fn penalty(&self) -> u8 {
+        self.penalty
+    }
+
+    /// Starts recording the game, so that steps can be undone
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub const fn with_history(self) -> RecordedGame {
+        RecordedGame::new(self)
+    }
+
+    /// Plays the game one card at a time, calling [`Game::step`] for each turn until the game is over.
+    ///
+    /// There's no turn limit, so an infinite game never ends; use [`Iterator::take`] to stop early.
+    /// Afterwards, the game is left in its final position, so [`Game::winner`] can be checked.
+    pub const fn turns(&mut self) -> Turns<'_> {
+        Turns { game: self }
+    }
+
+    /// Plays a single card, returning what happened, or `None` if the game is over.
+    ///
+    /// Stepping until `None` plays out exactly the same game as [`Game::play`].
+    pub fn step(&mut self) -> Option<Turn> {
+        let player = self.to_move;
+        let (current_player, other_player) = match player {
+            Player::P1 => (&mut self.p1, &mut self.p2),
+            Player::P2 => (&mut self.p2, &mut self.p1),
+        };
+
+        // Like in `play`, the game is over once the player to move is down to their last card.
+        // Unlike `play`, we also stop on an empty hand so a malformed game can't pop from it.
+        if current_player.len() <= 1 {
+            return None;
+        }
+
+        let mut collected = false;
+
+        // Safety: the player has cards left, and a game never holds more than a deck,
+        // so neither the middle pile nor the other player's hand can overflow
+        let card = unsafe { current_player.pop_unchecked() };
+        unsafe { self.middle.push_unchecked(card) };
+
+        if card == Card::Other {
+            match self.penalty {
+                0 => self.to_move = player.other(),
+                1 => {
+                    self.to_move = player.other();
+
+                    unsafe { other_player.push_slice(self.middle.slice()) };
+                    self.middle.clear();
+
+                    self.penalty = 0;
+                    collected = true;
+                }
+                _ => self.penalty -= 1,
+            }
+        } else {
+            self.penalty = card.penalty();
+            self.to_move = player.other();
+        }
+
+        Some(Turn {
+            player,
+            card,
+            penalty: card != Card::Other,
+            collected,
+            pile: self.middle.len(),
+        })
+    }
+
+    /// Whether the game is over, which is when the player to move is down to their last card (or has none).
+    ///
+    /// This is the same check [`Game::play`] and [`Game::step`] stop on. The other player being down to
+    /// one card doesn't end the game, as they may still win cards back before they have to play it.
+    #[must_use]
+    pub const fn is_over(&self) -> bool {
+        match self.to_move {
+            Player::P1 => self.p1.len() <= 1,
+            Player::P2 => self.p2.len() <= 1,
+        }
+    }
+
+    /// Plays out the game like [`Game::play`], but calls `observe` after every card is played
+    /// with the game and what happened.
+    ///
+    /// The closure is called once per card, including a card that fails to pay a penalty
+    /// (after which the middle pile has already been collected). This steps through the game
+    /// one card at a time, so it is slower than `play`, which stays free of any instrumentation.
+    pub fn play_observed<F: FnMut(&Self, &Turn)>(&mut self, observe: F) -> GameStats {
+        self.play_stepped(TURN_LIMIT, observe)
+    }
+
+    /// Plays out the game like [`Game::play`], also returning a snapshot from every time the
+    /// middle pile was collected, in order.
+    ///
+    /// This only stores one snapshot per trick won, so it stays small even for the longest games.
+    #[cfg(feature = "alloc")]
+    pub fn play_tracing(&mut self) -> (GameStats, alloc::vec::Vec<TrickSnapshot>) {
+        let mut snapshots = alloc::vec::Vec::new();
+        let stats = self.play_tracing_into(&mut snapshots);
+
+        (stats, snapshots)
+    }
+
+    /// Like [`Game::play_tracing`], but fills `snapshots` (clearing it first) instead of allocating,
+    /// so one buffer can be reused across many games.
+    #[cfg(feature = "alloc")]
+    pub fn play_tracing_into(
+        &mut self,
+        snapshots: &mut alloc::vec::Vec<TrickSnapshot>,
+    ) -> GameStats {
+        snapshots.clear();
+        let mut turn = 0;
+        let mut pile = self.middle.len();
+
+        self.play_observed(|game, played| {
+            turn += 1;
+
+            if played.collected {
+                snapshots.push(TrickSnapshot {
+                    turn,
+                    collector: played.player.other(),
+                    // the pile before this card, plus the card itself
+                    pile: pile + 1,
+                    p1_cards: game.p1.len(),
+                    p2_cards: game.p2.len(),
+                });
+            }
+
+            pile = played.pile;
+        })
+    }
+
+    /// Plays out the game like [`Game::play`], also returning how many cards were played in each trick, in order.
+    ///
+    /// A trick here runs from just after one collection of the middle pile up to and including the card that
+    /// failed to pay for the next, so there's one length per [`GameStats::pickups`], and the cards played after
+    /// the last collection aren't included. No trick can be longer than a deck, so each fits in a `u16`.
+    #[cfg(feature = "alloc")]
+    pub fn play_with_trick_lengths(&mut self) -> (GameStats, alloc::vec::Vec<u16>) {
+        let mut lengths = alloc::vec::Vec::new();
+        let mut length = 0;
+
+        let stats = self.play_observed(|_, played| {
+            length += 1;
+
+            if played.collected {
+                lengths.push(length);
+                length = 0;
+            }
+        });
+
+        (stats, lengths)
+    }
+
+    /// Plays out the game like [`Game::play`], also returning every card played to the middle pile, in order.
+    ///
+    /// Unlike [`Game::pile_cards`], this isn't cleared when the middle pile is collected,
+    /// so it is a transcript of the whole game (one card per turn, apart from the loser's final card).
+    #[cfg(feature = "alloc")]
+    pub fn play_transcript(&mut self) -> (GameStats, alloc::vec::Vec<Card>) {
+        let mut transcript = alloc::vec::Vec::new();
+        let stats = self.play_transcript_into(&mut transcript);
+
+        (stats, transcript)
+    }
+
+    /// Like [`Game::play_transcript`], but fills `transcript` (clearing it first) instead of allocating,
+    /// so one buffer can be reused across many games.
+    #[cfg(feature = "alloc")]
+    pub fn play_transcript_into(&mut self, transcript: &mut alloc::vec::Vec<Card>) -> GameStats {
+        transcript.clear();
+        self.play_observed(|_, turn| transcript.push(turn.card))
+    }
+
+    /// Plays out a copy of the game to find the turn it was effectively decided on: the turn after which
+    /// the eventual loser never again held a penalty card in their hand.
+    ///
+    /// Turns are counted like in [`TrickSnapshot::turn`], so `Some(0)` means the loser never held one at all.
+    /// This is `None` if the game reaches [`TURN_LIMIT`] without a winner.
+    #[must_use]
+    pub fn decisive_turn(&self) -> Option<usize> {
+        let holds_penalty = |hand: &[Card]| hand.iter().any(|&card| card != Card::Other);
+
+        // the last turn after which each player held a penalty card
+        let mut last_held = [
+            holds_penalty(self.p1_cards()).then_some(0),
+            holds_penalty(self.p2_cards()).then_some(0),
+        ];
+        let mut turn = 0;
+
+        let stats = self.clone().play_observed(|game, _| {
+            turn += 1;
+
+            for (held, hand) in last_held.iter_mut().zip([game.p1_cards(), game.p2_cards()]) {
+                if holds_penalty(hand) {
+                    *held = Some(turn);
+                }
+            }
+        });
+
+        let Termination::Win(winner) = stats.terminated else {
+            return None;
+        };
+
+        let loser = match winner.other() {
+            Player::P1 => 0,
+            Player::P2 => 1,
+        };
+
+        Some(last_held[loser].map_or(0, |turn| turn + 1))
+    }
+
+    /// Plays out the game like [`Game::play`], but instead of a turn limit, remembers the state after every
+    /// trick is won and returns `true` as soon as one repeats, which proves the game will loop forever.
+    ///
+    /// Every infinite game keeps winning tricks, so it is enough to only compare the states straight
+    /// after the middle pile is collected. A cycling game is reported as [`Termination::LimitReached`],
+    /// with the stats up to the first repeated state. Once more than `max_states` states have been seen
+    /// without a repeat, this gives up with [`Termination::LimitReached`] and `false`, since the game may just be long.
+    #[cfg(feature = "std")]
+    pub fn play_detect_cycle(&mut self, max_states: usize) -> (GameStats, bool) {
+        let mut stats = GameStats::start(self.middle.len());
+        let mut chain = GameStats::penalty_chain(self.pile_cards());
+        let mut seen = std::collections::HashSet::new();
+
+        loop {
+            let penalty = self.penalty;
+            let pile = self.middle.len();
+
+            let Some(turn) = self.step() else {
+                stats.terminated = Termination::Win(self.to_move.other());
+                return (stats, false);
+            };
+
+            stats.tally(&turn, penalty, pile, &mut chain);
+
+            if turn.collected {
+                if !seen.insert(self.clone()) {
+                    return (stats, true);
+                }
+
+                if seen.len() > max_states {
+                    return (stats, false);
+                }
+            }
+        }
+    }
+
+    /// The stepping equivalent of [`Game::play_with_limit`], calling `observe` after every card
+    fn play_stepped(&mut self, max_turns: usize, observe: impl FnMut(&Self, &Turn)) -> GameStats {
+        self.play_stepped_until(max_turns, |_| false, observe)
+    }
+
+    /// Like [`Game::play_stepped`], but also pausing the game as soon as `pause` is true of the stats so far
+    fn play_stepped_until(
+        &mut self,
+        max_turns: usize,
+        pause: impl Fn(&GameStats) -> bool,
+        mut observe: impl FnMut(&Self, &Turn),
+    ) -> GameStats {
+        let mut stats = GameStats::start(self.middle.len());
+        let mut chain = GameStats::penalty_chain(self.pile_cards());
+
+        stats.terminated = loop {
+            if (stats.turns >= max_turns || pause(&stats)) && !self.is_over() {
+                break Termination::LimitReached;
+            }
+
+            let penalty = self.penalty;
+            let pile = self.middle.len();
+
+            let Some(turn) = self.step() else {
+                break Termination::Win(self.to_move.other());
+            };
+
+            stats.tally(&turn, penalty, pile, &mut chain);
+            observe(self, &turn);
+        };
+
+        stats
+    }
+
+    /// Plays until the middle pile has been collected `n` times, leaving the game paused just after
+    /// (so its [`Debug`] notation can be saved and played on from later), and returns the stats so far.
+    ///
+    /// Like [`Game::play_with_limit`], `terminated` is [`Termination::LimitReached`] unless the game was won first.
+    /// This also stops at [`TURN_LIMIT`], so it can't run forever on an infinite game.
+    pub fn play_to_trick(&mut self, n: usize) -> GameStats {
+        self.play_stepped_until(TURN_LIMIT, |stats| stats.pickups >= n, |_, _| {})
+    }
+
+    /// Plays out a game of beggar my neighbour, returning how many steps it took
+    ///
+    /// Gives up after [`TURN_LIMIT`] turns, see [`Game::play_with_limit`].
+    pub fn play(&mut self) -> GameStats {
+        self.play_with_limit(TURN_LIMIT)
+    }
+
+    /// Plays out the game like [`Game::play_with_limit`], but instead of stopping at `cap`, plays another
+    /// [`DIAGNOSIS_WINDOW`] turns to record which way the hands are heading. This helps tell a game
+    /// that's really looping from one that's just slow, as a slow game is still won during the window.
+    ///
+    /// This steps through the game one card at a time like [`Game::play_observed`], so it is slower than `play`.
+    pub fn play_diagnostic(&mut self, cap: usize) -> Diagnosis {
+        // like in `play`, the loser's final card counts as a turn
+        let mut turns = 1;
+        let mut window: Option<HandTrend> = None;
+        // how many cards p1 held in total, and on how many turns, over the first and last quarters of the window
+        let (mut early, mut late) = ((0, 0), (0, 0));
+
+        let stats = self.play_stepped(cap.saturating_add(DIAGNOSIS_WINDOW), |game, _| {
+            turns += 1;
+            if turns < cap {
+                return;
+            }
+
+            let held = game.p1.len();
+            let window = window.get_or_insert(HandTrend {
+                first: held,
+                last: held,
+                min: held,
+                max: held,
+                trend: Trend::Oscillating,
+            });
+
+            window.last = held;
+            window.min = window.min.min(held);
+            window.max = window.max.max(held);
+
+            let into_window = turns - cap;
+            if into_window < DIAGNOSIS_WINDOW / 4 {
+                early = (early.0 + held, early.1 + 1);
+            } else if into_window >= DIAGNOSIS_WINDOW - DIAGNOSIS_WINDOW / 4 {
+                late = (late.0 + held, late.1 + 1);
+            }
+        });
+
+        let trend = match stats.terminated {
+            Termination::Win(_) => None,
+            Termination::LimitReached => window.map(|mut window| {
+                let mean = |(total, count): (usize, usize)| total as f64 / count.max(1) as f64;
+                let moved = mean(late) - mean(early);
+                let range = (window.max - window.min) as f64;
+
+                window.trend = if moved > 0.0 && 4.0 * moved >= range {
+                    Trend::Growing
+                } else if moved < 0.0 && -4.0 * moved >= range {
+                    Trend::Shrinking
+                } else {
+                    Trend::Oscillating
+                };
+
+                window
+            }),
+        };
+
+        Diagnosis { stats, trend }
+    }
+
+    /// Plays out the game like [`Game::play`], returning the winner along with the stats.
+    ///
+    /// The winner comes from how the game stopped, so it is always [`Winner::Infinite`] for a game cut off
+    /// by [`TURN_LIMIT`] and never needs a separate call to [`Game::winner`].
+    pub fn play_result(&mut self) -> GameResult {
+        let stats = self.play();
+
+        GameResult {
+            winner: stats.terminated.into(),
+            stats,
+        }
+    }
+
+    /// Plays out a game of beggar my neighbour, returning how many steps it took,
+    /// or stopping as soon as `max_turns` turns have been played.
+    ///
+    /// The limit is checked before every card is played, so `turns` never goes past `max_turns`
+    /// and can't overflow, even for an infinite game played with a limit of `usize::MAX`.
+    pub fn play_with_limit(&mut self, max_turns: usize) -> GameStats {
+        self.play_with_penalties(max_turns, Card::penalty, PickupOrder::Forward)
+    }
+
+    /// Plays out the game like [`Game::play`], but with the penalty card values from `rules`
+    pub fn play_with_rules(&mut self, rules: &Rules) -> GameStats {
+        let penalties = rules.penalties();
+        self.play_with_penalties(TURN_LIMIT, |card| penalties[card as usize], rules.pickup)
+    }
+
+    /// The game loop shared by every way of playing a whole game at full speed,
+    /// with `penalty` giving how many cards each penalty card makes the other player pay
+    /// and `pickup` the order a collected middle pile is added in
+    ///
+    /// A card is only a penalty card if `penalty` gives it a penalty, so any card can play like an Other.
+    #[inline(always)]
+    fn play_with_penalties(
+        &mut self,
+        max_turns: usize,
+        penalty: impl Fn(Card) -> u8,
+        pickup: PickupOrder,
+    ) -> GameStats {
+        // The loser's final card counts as a turn but is never played (see `GameStats`),
+        // so start from 1 rather than adding it once the game is over
+        let mut turns = 1;
+        let mut tricks = 0;
+        let mut pickups = 0;
+        // every card played is moved once, so only the collected cards need counting
+        let mut collected: usize = 0;
+        let mut p1_tricks = 0;
+        let mut p2_tricks = 0;
+        let mut max_pile = self.middle.len();
+        // penalty cards already in the middle pile belong to the trick being played
+        let mut penalty_chain = self
+            .middle
+            .iter()
+            .filter(|&&card| penalty(card) > 0)
+            .count();
+        let mut longest_penalty_chain = 0;
+
+        let mut to_move = self.to_move;
+        let (mut current_player, mut other_player) = match to_move {
+            Player::P1 => (&mut self.p1, &mut self.p2),
+            Player::P2 => (&mut self.p2, &mut self.p1),
+        };
+
+        let terminated = loop {
+            unsafe {
+                // We can return early (len = 1) because regardless of the card played, the game is over.
+                // A hand-written game can also start with an empty hand, which has already lost.
+                if (*current_player).len() <= 1 {
+                    break Termination::Win(to_move.other());
+                }
+
+                // This is checked every card (rather than every collection)
+                // so a game which never collects still stops
+                if turns >= max_turns {
+                    break Termination::LimitReached;
+                }
+
+                // we can safely pop here because we know the player has cards (otherwise the game would be over)
+                // *unless current_player.len() == 0, which is impossible we only remove 1 card at a time
+                let card = (*current_player).pop_unchecked();
+                self.middle.push_unchecked(card);
+                max_pile = max_pile.max(self.middle.len());
+                turns += 1;
+
+                let demanded = penalty(card);
+                if demanded == 0 {
+                    match self.penalty {
+                        0 => {
+                            core::mem::swap(&mut current_player, &mut other_player);
+                            to_move = to_move.other();
+                        }
+                        // If the penalty is 1 and the player hasn't played a penalty card, the other player takes all the cards
+                        // from the middle and adds them to the beginning of their deck
+                        1 => {
+                            core::mem::swap(&mut current_player, &mut other_player);
+                            to_move = to_move.other();
+
+                            pickups += 1;
+                            match to_move {
+                                Player::P1 => p1_tricks += 1,
+                                Player::P2 => p2_tricks += 1,
+                            }
+
+                            collected = collected.saturating_add(self.middle.len());
+                            match pickup {
+                                PickupOrder::Forward => {
+                                    (*current_player).push_slice(self.middle.slice());
+                                }
+                                PickupOrder::Reversed => {
+                                    for &card in self.middle.slice().iter().rev() {
+                                        (*current_player).push(card);
+                                    }
+                                }
+                            }
+                            self.middle.clear();
+
+                            self.penalty = 0;
+                            penalty_chain = 0;
+                        }
+                        _ => self.penalty -= 1,
+                    };
+                } else {
+                    // regardless if the game currently has penalty,
+                    // if the player plays a penalty card,
+                    // the penalty is set and the other player must play
+                    if self.penalty == 0 {
+                        tricks += 1;
+                    }
+                    penalty_chain += 1;
+                    longest_penalty_chain = longest_penalty_chain.max(penalty_chain);
+                    self.penalty = demanded;
+                    core::mem::swap(&mut current_player, &mut other_player);
+                    to_move = to_move.other();
+                }
+            }
+        };
+
+        self.to_move = to_move;
+
+        GameStats {
+            turns,
+            tricks,
+            pickups,
+            p1_tricks,
+            p2_tricks,
+            max_pile,
+            longest_penalty_chain,
+            // the loser's final card counts as a turn but is never moved
+            cards_moved: (turns - 1).saturating_add(collected),
+            terminated,
+        }
+    }
+}
+
+/// The unshuffled deck from [`new_deck`], split down the middle: p1 holds every face card and Ace
+/// followed by 10 Others (`AAAAKKKKQQQQJJJJ----------`), and p2 holds the other 26 Others.
+///
+/// This needs no randomness, so it's a fixed starting point for tests and examples.
+impl Default for Game {
+    fn default() -> Self {
+        Self::deal(&new_deck())
+    }
+}
+
+/// Shows each hand and the middle pile on its own line.
+/// The alternate form (`{:#}`) adds the penalty being paid and whose turn it is.
+impl Display for Game {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "p1: ")?;
+        for card in self.p1.iter() {
+            write!(f, "{card}")?;
+        }
+
+        write!(f, "\np2: ")?;
+        for card in self.p2.iter() {
+            write!(f, "{card}")?;
+        }
+
+        if !self.middle.is_empty() {
+            write!(f, "\nmiddle: ")?;
+            for card in self.middle.iter() {
+                write!(f, "{card}")?;
+            }
+        }
+
+        // `{:#}` also shows the state that isn't in the cards
+        if f.alternate() {
+            write!(
+                f,
+                "\npenalty: {penalty}, to move: {to_move:?}",
+                penalty = self.penalty,
+                to_move = self.to_move
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The notation [`Game::try_from_string`] parses. A fresh game is just `p1/p2`, and anything
+/// further into a game also has the middle pile, the penalty and whose turn it is.
+impl Debug for Game {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for card in self.p1.iter() {
+            write!(f, "{card}")?;
+        }
+
+        write!(f, "/")?;
+
+        for card in self.p2.iter() {
+            write!(f, "{card}")?;
+        }
+
+        if !self.middle.is_empty() || self.penalty > 0 {
+            write!(f, "/")?;
+
+            for card in self.middle.iter() {
+                write!(f, "{card}")?;
+            }
+        }
+
+        if self.penalty > 0 {
+            write!(f, "+{}", self.penalty)?;
+        }
+
+        if self.to_move == Player::P2 {
+            write!(f, "@2")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        diverge_turn, hand_advantage, is_new_record, new_deck, shuffle_deck_seeded, Card, Game,
+        HandCounts, InvalidCardValue, InvalidDeck, ParseError, PickupOrder, Player, Rules,
+        Termination, Trend, TrickSnapshot, Winner, DECK_SIZE, DIAGNOSIS_WINDOW, KNOWN_RECORD_TURNS,
+        TURN_LIMIT, WORLD_RECORD_DECK, WORLD_RECORD_STATS,
+    };
+
+    fn assert_game(deck: &str, turns: usize, tricks: usize) {
+        let game = &mut Game::from_string(deck);
+
+        let stats = game.play();
+
+        assert_eq!(stats.turns, turns);
+        assert_eq!(stats.tricks, tricks);
+        assert_eq!(Winner::from(stats.terminated), game.winner());
+
+        let mut observed = 0;
+        let observed_stats = Game::from_string(deck).play_observed(|_, _| observed += 1);
+        assert_eq!(observed_stats, stats);
+        assert_eq!(observed + 1, turns);
+    }
+
+    #[test]
+    fn regression_corpus() {
+        let corpus = include_str!("corpus.txt")
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+        for line in corpus {
+            let fields: Vec<_> = line.split_whitespace().collect();
+            let [deck, turns, tricks, winner] = fields[..] else {
+                panic!("corpus line {line:?} should be `deck turns tricks winner`");
+            };
+
+            assert_game(deck, turns.parse().unwrap(), tricks.parse().unwrap());
+
+            let winner = match winner {
+                "P1" => Winner::P1,
+                "P2" => Winner::P2,
+                "Infinite" => Winner::Infinite,
+                _ => panic!("corpus line {line:?} has an unknown winner"),
+            };
+            assert_eq!(
+                Game::from_string(deck).play_result().winner,
+                winner,
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn world_record() {
+        assert_eq!(
+            Game::from_string(WORLD_RECORD_DECK).play(),
+            WORLD_RECORD_STATS
+        );
+        assert!(!is_new_record(KNOWN_RECORD_TURNS));
+        assert!(is_new_record(8_345));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Game::try_from_string("AK-/Q-x").unwrap_err(),
+            ParseError::InvalidChar {
+                found: 'x',
+                offset: 6
+            }
+        );
+        assert_eq!(
+            Game::try_from_string("AKQJ").unwrap_err(),
+            ParseError::WrongSectionCount(1)
+        );
+        assert_eq!(
+            Game::try_from_string("A/K/Q/J").unwrap_err(),
+            ParseError::WrongSectionCount(4)
+        );
+        assert_eq!(
+            Game::try_from_string(&format!("{}/{}", "-".repeat(30), "-".repeat(30))).unwrap_err(),
+            ParseError::WrongDeckSize { p1: 30, p2: 30 }
+        );
+    }
+
+    #[test]
+    fn loose_notation() {
+        assert_eq!(
+            Game::from_string("a k -\n/ q j\n"),
+            Game::from_string("AK-/QJ")
+        );
+        assert_eq!(
+            Game::from_string(" a-/-j / k +2@2 \n"),
+            Game::from_string("A-/-J/K+2@2")
+        );
+
+        // spaces don't count towards the deck size
+        let spaced = format!("{} / {}", "- ".repeat(26), "- ".repeat(26));
+        assert_eq!(Game::from_string(&spaced).p1_cards().len(), 26);
+
+        assert_eq!(
+            Game::try_from_string_strict("a/K").unwrap_err(),
+            ParseError::InvalidChar {
+                found: 'a',
+                offset: 0
+            }
+        );
+        assert_eq!(
+            Game::try_from_string_strict("A /K").unwrap_err(),
+            ParseError::InvalidChar {
+                found: ' ',
+                offset: 1
+            }
+        );
+        assert_eq!(
+            Game::try_from_string_strict(WORLD_RECORD_DECK),
+            Ok(Game::from_string(WORLD_RECORD_DECK))
+        );
+    }
+
+    #[test]
+    fn validate_deck() {
+        assert!(Game::from_string(WORLD_RECORD_DECK).validate().is_ok());
+        assert_eq!(
+            Game::from_string("AAAA/KKKK").validate(),
+            Err(InvalidDeck {
+                card: Card::Queen,
+                expected: 4,
+                found: 0
+            })
+        );
+        assert_eq!(
+            Game::from_string("A---AJ--Q---------QAKQJJ-QK/-----A----KJ-K--------A--").validate(),
+            Err(InvalidDeck {
+                card: Card::Ace,
+                expected: 4,
+                found: 5
+            })
+        );
+    }
+
+    #[test]
+    fn step_matches_play() {
+        let deck = WORLD_RECORD_DECK;
+
+        let mut stepped = Game::from_string(deck);
+        let mut steps = 0;
+        while !stepped.is_over() {
+            assert!(stepped.step().is_some());
+            steps += 1;
+        }
+        assert!(stepped.step().is_none());
+        assert_eq!(stepped.winner(), stepped.to_move().other().into());
+
+        let mut played = Game::from_string(deck);
+        let stats = played.play();
+
+        // `play` counts the loser's final card as a turn, which is never stepped
+        assert_eq!(steps + 1, stats.turns);
+        assert_eq!(format!("{stepped:?}"), format!("{played:?}"));
+    }
+
+    #[test]
+    fn turn_limit() {
+        let mut game = Game::from_string(WORLD_RECORD_DECK);
+
+        let stats = game.play_with_limit(1_000);
+
+        assert_eq!(stats.turns, 1_000);
+        assert_eq!(stats.terminated, Termination::LimitReached);
+        assert_eq!(game.winner(), Winner::Infinite);
+        assert_eq!(
+            Game::from_string(WORLD_RECORD_DECK).play_stepped(1_000, |_, _| {}),
+            stats
+        );
+
+        // picking the game back up plays it out to its real end
+        assert_eq!(game.play().terminated, Termination::Win(Player::P2));
+    }
+
+    #[test]
+    fn seeded_games_repeat() {
+        let mut deck = new_deck();
+        let game = Game::random_seeded(&mut deck, 42);
+
+        // shuffling the scratch deck in between shouldn't matter
+        let _ = Game::random_with_rng(&mut deck, &mut fastrand::Rng::with_seed(7));
+
+        assert_eq!(
+            format!("{game:?}"),
+            format!("{:?}", Game::random_seeded(&mut deck, 42))
+        );
+    }
+
+    #[test]
+    fn middle_pile_order() {
+        let mut game = Game::from_string("--A/K--");
+
+        while game.step().is_some() {}
+
+        assert_eq!(game.middle_len(), 3);
+        assert_eq!(game.pile_cards(), [Card::Other, Card::King, Card::Other]);
+        assert_eq!(Game::from_string("--A/K--").play().max_pile, 3);
+
+        // p1 fails to pay p2's King, so p2 collects
+        let stats = Game::from_string("----A/K--").play();
+        assert_eq!((stats.p1_tricks, stats.p2_tricks), (0, 1));
+        assert_eq!(stats.pickups, 1);
+    }
+
+    #[test]
+    fn deck_ids() {
+        let record = WORLD_RECORD_DECK;
+
+        // pinned so that ids saved by older versions stay valid
+        assert_eq!(Game::from_string(record).deck_id(), 0x516c_2c06_c40d_809a);
+        assert_ne!(
+            Game::from_string("A-/K").deck_id(),
+            Game::from_string("A/-K").deck_id()
+        );
+    }
+
+    #[test]
+    fn rotations() {
+        let rotations: Vec<_> = Game::from_string("AK-/QJ")
+            .rotations()
+            .map(|game| format!("{game:?}"))
+            .collect();
+
+        assert_eq!(
+            rotations,
+            ["AK-/QJ", "AK-/JQ", "K-A/QJ", "K-A/JQ", "-AK/QJ", "-AK/JQ"]
+        );
+    }
+
+    #[test]
+    fn hands_after_wraparound() {
+        let mut game = Game::from_string(WORLD_RECORD_DECK);
+
+        // long enough for both hands to wrap around their buffers many times
+        game.play_with_limit(5_000);
+
+        assert_eq!(
+            game.p1_cards().len() + game.p2_cards().len() + game.middle_len(),
+            52
+        );
+        assert!(game.validate().is_ok());
+    }
+
+    #[test]
+    fn packed_round_trip() {
+        for deck in [WORLD_RECORD_DECK, "A-K/", "/"] {
+            let packed = Game::from_string(deck).to_packed();
+            assert_eq!(format!("{:?}", Game::from_packed(&packed).unwrap()), deck);
+        }
+
+        assert_eq!(
+            Game::from_packed(&[0; 3]).unwrap_err(),
+            ParseError::WrongPackedSize(3)
+        );
+        assert_eq!(
+            Game::from_packed(&[0b0000_0110; 20]).unwrap_err(),
+            ParseError::InvalidCode { code: 6, offset: 0 }
+        );
+    }
+
+    #[test]
+    fn verbose_display() {
+        let mut game = Game::from_string("-K-/Q--");
+        game.step();
+        game.step();
+
+        assert_eq!(format!("{game}"), "p1: K-\np2: --\nmiddle: -Q");
+        assert_eq!(
+            format!("{game:#}"),
+            "p1: K-\np2: --\nmiddle: -Q\npenalty: 2, to move: P1"
+        );
+    }
+
+    #[test]
+    fn hand_counts() {
+        let mut game = Game::from_string(WORLD_RECORD_DECK);
+        let (p1, p2) = game.hand_counts();
+
+        assert_eq!(
+            p1,
+            HandCounts {
+                aces: 2,
+                kings: 2,
+                queens: 4,
+                jacks: 3,
+                others: 15
+            }
+        );
+        assert_eq!(p2.count(Card::Ace), 2);
+        assert_eq!(p2.total(), 26);
+
+        game.play();
+        let (p1, p2) = game.hand_counts();
+        assert_eq!(p1.total(), 1);
+        assert_eq!(p1.total() + p2.total() + game.middle_len(), 52);
+    }
+
+    #[test]
+    fn equality_ignores_layout() {
+        use std::collections::HashSet;
+
+        let deck = WORLD_RECORD_DECK;
+        let game = Game::from_string(deck);
+        assert_eq!(game, Game::from_packed(&game.to_packed()).unwrap());
+
+        let rotations: HashSet<_> = Game::from_string("AK-/QJ").rotations().collect();
+        assert_eq!(rotations.len(), 6);
+        let rotations: HashSet<_> = Game::from_string("---/--").rotations().collect();
+        assert_eq!(rotations.len(), 1);
+
+        // the same cards with a different player to move aren't the same game
+        let p2_to_move = Game {
+            to_move: Player::P2,
+            ..Game::from_string("A/--")
+        };
+        assert_ne!(p2_to_move, Game::from_string("A/--"));
+    }
+
+    #[test]
+    fn reset_to_deck() {
+        let mut deck = new_deck();
+        let dealt = Game::random_seeded(&mut deck, 3);
+
+        let mut game = Game::from_string(WORLD_RECORD_DECK);
+        game.play();
+        game.reset_to_deck(&deck);
+
+        assert_eq!(game, dealt);
+        assert_eq!(game.play(), dealt.clone().play());
+    }
+
+    #[test]
+    fn minimal_games() {
+        // p1 starts on their last card, which is the only turn
+        assert_game("A/-", 1, 0);
+        assert_game("A/-K", 1, 0);
+
+        // p1's Ace starts a trick, then p2's last card would fail to pay it
+        assert_game("A-/-", 2, 1);
+        assert_game("-A/-", 2, 0);
+
+        // p2's King covers the Ace, carrying on the same trick, before p1 is down to their last card
+        assert_game("A-/K-", 3, 1);
+
+        assert_eq!(
+            Game::from_string("A/-").play().terminated,
+            Termination::Win(Player::P2)
+        );
+        assert_eq!(
+            Game::from_string("A-/-").play().terminated,
+            Termination::Win(Player::P1)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn trick_snapshots() {
+        let (stats, snapshots) = Game::from_string("----A/K--").play_tracing();
+        assert_eq!(stats, Game::from_string("----A/K--").play());
+        assert_eq!(snapshots.len(), stats.pickups);
+
+        let (stats, snapshots) = Game::from_string(WORLD_RECORD_DECK).play_tracing();
+        assert_eq!(snapshots.len(), stats.pickups);
+        assert_eq!(
+            snapshots
+                .iter()
+                .filter(|snapshot| snapshot.collector == Player::P1)
+                .count(),
+            stats.p1_tricks
+        );
+        assert!(snapshots
+            .iter()
+            .all(|snapshot| snapshot.p1_cards + snapshot.p2_cards == 52));
+        assert!(snapshots.windows(2).all(|pair| pair[0].turn < pair[1].turn));
+        assert_eq!(
+            snapshots.iter().map(|snapshot| snapshot.pile).max(),
+            Some(stats.max_pile)
+        );
+
+        // a reused buffer only holds the latest game's snapshots
+        let mut buffer = snapshots.clone();
+        Game::from_string("----A/K--").play_tracing_into(&mut buffer);
+        assert_eq!(buffer, Game::from_string("----A/K--").play_tracing().1);
+    }
+
+    #[test]
+    fn hand_advantages() {
+        let snapshot = |turn, p1_cards| TrickSnapshot {
+            turn,
+            collector: Player::P1,
+            pile: 2,
+            p1_cards,
+            p2_cards: 52 - p1_cards,
+        };
+
+        assert_eq!(hand_advantage(&[]), 0.0);
+        assert_eq!(hand_advantage(&[snapshot(5, 30)]), 8.0);
+        // 10 turns 8 ahead, then 30 turns 4 behind, and the last snapshot only ends the game
+        assert_eq!(
+            hand_advantage(&[snapshot(10, 30), snapshot(20, 24), snapshot(50, 0)]),
+            (8.0 * 10.0 - 4.0 * 30.0) / 40.0
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn uneven_deals() {
+        let game = Game::random_split(&mut new_deck(), 30);
+
+        assert_eq!((game.p1_cards().len(), game.p2_cards().len()), (30, 22));
+        assert_eq!(game.validate(), Ok(()));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn detects_cycles() {
+        let record = WORLD_RECORD_DECK;
+        let (stats, cycle) = Game::from_string(record).play_detect_cycle(10_000);
+        assert!(!cycle);
+        assert_eq!(stats, Game::from_string(record).play());
+
+        // the first infinite game found with a full deck
+        let infinite = "---K---Q-KQAJ-----AAJ--J--/----------Q----KQ-J-----KA";
+        let (stats, cycle) = Game::from_string(infinite).play_detect_cycle(10_000);
+        assert!(cycle);
+        assert_eq!(stats.terminated, Termination::LimitReached);
+
+        assert!(Game::from_string("J--/-J-").play_detect_cycle(10_000).1);
+        assert!(!Game::from_string(infinite).play_detect_cycle(1).1);
+    }
+
+    #[test]
+    fn card_values() {
+        for card in Card::ALL {
+            assert_eq!(Card::try_from(card as u8), Ok(card));
+        }
+
+        assert_eq!(Card::try_from(0), Ok(Card::Other));
+        assert_eq!(Card::try_from(5), Err(InvalidCardValue(5)));
+        assert_eq!(Card::from_u8(u8::MAX), None);
+    }
+
+    #[test]
+    fn penalty_chains() {
+        // K, then Q answering it, then J answering that, before p2 fails to pay
+        assert_eq!(
+            Game::from_string("KJ--/Q---").play().longest_penalty_chain,
+            3
+        );
+        assert_eq!(
+            Game::from_string("----/---").play().longest_penalty_chain,
+            0
+        );
+
+        let stats = Game::from_string(WORLD_RECORD_DECK).play();
+        assert!((1..=16).contains(&stats.longest_penalty_chain));
+
+        // the K and J already in the middle pile carry on into the Q answering them,
+        // however the game is played
+        let midway = Game::from_string("---/Q--/KJ+1@2");
+        let stats = midway.clone().play();
+        assert_eq!(stats.longest_penalty_chain, 3);
+        assert_eq!(midway.clone().play_observed(|_, _| {}), stats);
+        #[cfg(feature = "alloc")]
+        assert_eq!(crate::safe::SafeGame::from(&midway).play(), stats);
+    }
+
+    #[test]
+    fn canonical_forms() {
+        let game = Game::from_string("K-A/QJ-");
+        let canonical = game.canonical();
+
+        assert_eq!(format!("{canonical:?}"), "-AK/-QJ");
+        assert_eq!(canonical.canonical(), canonical);
+        for rotation in game.rotations() {
+            assert_eq!(rotation.canonical(), canonical);
+            assert_eq!(rotation.swapped().canonical(), canonical);
+        }
+
+        // swapping seats plays the same game for the other player
+        let record = Game::from_string(WORLD_RECORD_DECK);
+        let (stats, swapped_stats) = (record.clone().play(), record.swapped().play());
+        assert_eq!(stats.turns, swapped_stats.turns);
+        assert_eq!(stats.terminated, Termination::Win(Player::P2));
+        assert_eq!(swapped_stats.terminated, Termination::Win(Player::P1));
+    }
+
+    #[test]
+    fn default_is_the_unshuffled_deck() {
+        let game = Game::default();
+
+        assert_eq!(
+            format!("{game:?}"),
+            format!("AAAAKKKKQQQQJJJJ----------/{}", "-".repeat(26))
+        );
+        assert_eq!(game.to_move(), Player::P1);
+        assert!(game.validate().is_ok());
+    }
+
+    #[test]
+    fn penalty() {
+        let mut game = Game::from_string("--/K--");
+        assert_eq!(game.penalty(), 0);
+
+        game.step();
+        game.step();
+        assert_eq!(game.penalty(), 3);
+        assert_eq!(game.to_move(), Player::P1);
+        assert_eq!(format!("{game:?}"), "-/--/-K+3");
+    }
+
+    #[test]
+    fn house_rules() {
+        let record = WORLD_RECORD_DECK;
+        assert_eq!(
+            Game::from_string(record).play_with_rules(&Rules::default()),
+            Game::from_string(record).play()
+        );
+
+        // a King is usually paid with 3 cards, which p2 can't afford
+        let rules = Rules {
+            king: 1,
+            ..Rules::default()
+        };
+        assert_eq!(Game::from_string("K--/---").play().turns, 4);
+
+        let mut game = Game::from_string("K--/---");
+        let stats = game.play_with_rules(&rules);
+        assert_eq!(stats.turns, 6);
+        assert_eq!(stats.pickups, 1);
+        assert_eq!(game.winner(), Winner::P1);
+
+        // without penalty Aces, p2 doesn't have to pay and p1 runs out first
+        assert_eq!(Rules::classic(), Rules::default());
+        let rules = Rules::faces_only();
+        for card in Card::ALL {
+            assert_eq!(rules.penalties()[card as usize], rules.penalty(card));
+        }
+        let stats = Game::from_string("A-/--").play();
+        assert_eq!(
+            (stats.tricks, stats.terminated),
+            (1, Termination::Win(Player::P1))
+        );
+
+        let stats = Game::from_string("A-/--").play_with_rules(&Rules::faces_only());
+        assert_eq!(
+            (stats.tricks, stats.terminated),
+            (0, Termination::Win(Player::P2))
+        );
+        assert_eq!(stats.longest_penalty_chain, 0);
+    }
+
+    #[test]
+    fn decisive_turns() {
+        // p2 plays their only Jack on turn 2 and never gets another penalty card
+        assert_eq!(Game::from_string("-AA/J-").decisive_turn(), Some(2));
+        assert_eq!(Game::from_string("K--/---").decisive_turn(), Some(0));
+        assert_eq!(
+            Game::from_string("---K---Q-KQAJ-----AAJ--J--/----------Q----KQ-J-----KA")
+                .decisive_turn(),
+            None
+        );
+
+        let record = Game::from_string(WORLD_RECORD_DECK);
+        let decisive = record.decisive_turn().unwrap();
+        assert!(decisive > 0 && decisive < record.clone().play().turns);
+    }
+
+    #[test]
+    fn card_order() {
+        assert!(Card::Ace > Card::King && Card::Jack > Card::Other);
+        assert!(Card::ALL.windows(2).all(|pair| pair[0] > pair[1]));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn sorted_hands() {
+        let game = Game::from_string("-J-A/KQ-");
+        assert_eq!(
+            game.sorted_hand(Player::P1),
+            [Card::Ace, Card::Jack, Card::Other, Card::Other]
+        );
+        assert_eq!(
+            game.sorted_hand(Player::P2),
+            [Card::King, Card::Queen, Card::Other]
+        );
+
+        // does p2 hold all four Aces?
+        let record = Game::from_string(WORLD_RECORD_DECK);
+        assert!(!record.sorted_hand(Player::P2).starts_with(&[Card::Ace; 4]));
+    }
+
+    #[test]
+    fn card_codes() {
+        let game = Game::from_string("AK-/QJ");
+        assert!(game.p1_codes().eq([4, 3, 0]));
+        assert!(game.p2_codes().eq([2, 1]));
+        assert!(game
+            .p1_codes()
+            .map(Card::from_u8)
+            .eq(game.p1_cards().iter().copied().map(Some)));
+    }
+
+    #[test]
+    fn diverging_games() {
+        let record = Game::from_string(WORLD_RECORD_DECK);
+        assert_eq!(diverge_turn(&record, &record), None);
+
+        // resuming from notation plays exactly the same way
+        let mut paused = record.clone();
+        paused.play_to_trick(50);
+        assert_eq!(
+            diverge_turn(&paused, &Game::from_string(&format!("{paused:?}"))),
+            None
+        );
+
+        // p1's second card is the first to differ, on the third turn
+        assert_eq!(
+            diverge_turn(
+                &Game::from_string("-A--/K--"),
+                &Game::from_string("-J--/K--")
+            ),
+            Some(3)
+        );
+        // and a game that ends can't match one that carries on
+        assert_eq!(
+            diverge_turn(&Game::from_string("A/-"), &Game::from_string("A-/-")),
+            Some(1)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn trick_lengths() {
+        let (stats, lengths) = Game::from_string("----A/K--").play_with_trick_lengths();
+        assert_eq!(stats, Game::from_string("----A/K--").play());
+        // the first Other and the King, then three Others failing to pay for it
+        assert_eq!(lengths, [5]);
+
+        let (stats, lengths) = Game::from_string(WORLD_RECORD_DECK).play_with_trick_lengths();
+        assert_eq!(lengths.len(), stats.pickups);
+        assert_eq!(
+            lengths.iter().map(|&length| usize::from(length)).max(),
+            Some(stats.max_pile)
+        );
+        // every card is played in a trick apart from the few after the last collection
+        assert!(
+            lengths
+                .iter()
+                .map(|&length| usize::from(length))
+                .sum::<usize>()
+                < stats.turns
+        );
+    }
+
+    #[test]
+    fn paused_games() {
+        let mut game = Game::from_string(WORLD_RECORD_DECK);
+        let before = game.play_to_trick(100);
+        assert_eq!(before.pickups, 100);
+        assert_eq!(before.terminated, Termination::LimitReached);
+        assert_eq!(game.penalty(), 0);
+
+        // the paused game plays on from its notation to finish the record
+        let mut resumed = Game::from_string(&format!("{game:?}"));
+        assert_eq!(resumed, game);
+        let after = resumed.play();
+        assert_eq!(before.turns - 1 + after.turns, KNOWN_RECORD_TURNS);
+        assert_eq!(before.tricks + after.tricks, WORLD_RECORD_STATS.tricks);
+
+        // a game that ends first is just won
+        let stats = Game::from_string("A/-").play_to_trick(1);
+        assert_eq!(stats.terminated, Termination::Win(Player::P2));
+        assert_eq!(
+            Game::from_string(WORLD_RECORD_DECK).play_to_trick(usize::MAX),
+            WORLD_RECORD_STATS
+        );
+    }
+
+    #[test]
+    fn turn_limits() {
+        // an infinite game stops exactly at the limit, however big it is
+        for limit in [1, 2, 1_000, TURN_LIMIT] {
+            let stats = Game::from_string("J--/-J-").play_with_limit(limit);
+            assert_eq!(
+                (stats.turns, stats.terminated),
+                (limit, Termination::LimitReached)
+            );
+
+            let mut observed = Game::from_string("J--/-J-");
+            assert_eq!(observed.play_stepped(limit, |_, _| {}), stats);
+        }
+
+        // the loser's final card still counts, even with no turns allowed
+        assert_eq!(Game::from_string("J--/-J-").play_with_limit(0).turns, 1);
+        assert_eq!(
+            Game::from_string(WORLD_RECORD_DECK).play_with_limit(usize::MAX),
+            WORLD_RECORD_STATS
+        );
+    }
+
+    #[test]
+    fn diagnoses() {
+        let diagnosis = Game::from_string("J--/-J-").play_diagnostic(1_000);
+        assert_eq!(diagnosis.stats.terminated, Termination::LimitReached);
+        assert_eq!(diagnosis.stats.turns, 1_000 + DIAGNOSIS_WINDOW);
+        assert_eq!(
+            diagnosis.trend.map(|trend| trend.trend),
+            Some(Trend::Oscillating)
+        );
+
+        // a slow game is still won past the cap, so it isn't presumed infinite
+        for cap in [TURN_LIMIT, 8_000] {
+            let diagnosis = Game::from_string(WORLD_RECORD_DECK).play_diagnostic(cap);
+            assert_eq!(diagnosis.stats, WORLD_RECORD_STATS);
+            assert_eq!(diagnosis.trend, None);
+        }
+    }
+
+    #[test]
+    fn play_results() {
+        let result = Game::from_string(WORLD_RECORD_DECK).play_result();
+        assert_eq!(result.winner, Winner::P2);
+        assert_eq!(result.stats, WORLD_RECORD_STATS);
+
+        let result = Game::from_string("A-/--").play_result();
+        assert_eq!(result.winner, Winner::P1);
+        assert_eq!(result.stats.turns, 3);
+    }
+
+    #[test]
+    fn dealing_from_any_rng() {
+        let mut rng = fastrand::Rng::with_seed(7);
+        let game = Game::deal_from_rng(&mut new_deck(), |range| rng.usize(range));
+        assert_eq!(
+            game,
+            Game::random_with_rng(&mut new_deck(), &mut fastrand::Rng::with_seed(7))
+        );
+
+        // never swapping deals the deck as it is
+        assert_eq!(
+            Game::deal_from_rng(&mut new_deck(), |range| *range.end()),
+            Game::default()
+        );
+    }
+
+    #[test]
+    fn shuffling() {
+        let mut deck = new_deck();
+        shuffle_deck_seeded(&mut deck, 42);
+        assert_eq!(
+            format!("{:?}", Game::deal(&deck)),
+            format!("{:?}", Game::random_seeded(&mut new_deck(), 42))
+        );
+
+        let (p1, p2) = Game::deal(&deck).hand_counts();
+        assert_eq!(p1.total() + p2.total(), DECK_SIZE);
+        assert_eq!(p1.count(Card::Ace) + p2.count(Card::Ace), 4);
+    }
+
+    #[test]
+    fn from_hands() {
+        let record = Game::from_string(WORLD_RECORD_DECK);
+        let game = Game::from_hands(record.p1_cards(), record.p2_cards()).unwrap();
+        assert_eq!(game, record);
+
+        let deck = new_deck();
+        assert_eq!(
+            Game::from_hands(&deck[..20], &deck[..20]).unwrap_err(),
+            ParseError::WrongDeckSize { p1: 20, p2: 20 }
+        );
+        assert_eq!(
+            Game::from_hands(&deck[..26], &deck[..26]).unwrap_err(),
+            ParseError::InvalidDeck(InvalidDeck {
+                card: Card::Ace,
+                expected: 4,
+                found: 8
+            })
+        );
+    }
+
+    #[test]
+    fn empty_hands_have_lost() {
+        let mut game = Game::from_string("/AKQJ");
+        assert_eq!(game.winner(), Winner::P2);
+        assert_eq!(game.play().terminated, Termination::Win(Player::P2));
+
+        // p2 has lost before p1 even plays
+        let mut game = Game::from_string("AKQ/");
+        assert_eq!(game.winner(), Winner::P1);
+        assert_eq!(game.play().terminated, Termination::Win(Player::P1));
+        assert_eq!(game.winner(), Winner::P1);
+    }
+
+    #[test]
+    fn quick_estimates() {
+        assert_eq!(Game::default().quick_estimate(), 0);
+        assert_eq!(Game::from_string("AK--/J---").quick_estimate(), 2);
+
+        let record = Game::from_string(WORLD_RECORD_DECK);
+        assert_eq!(record.quick_estimate(), 10);
+    }
+
+    #[test]
+    fn random_deals_terminate() {
+        let mut rng = fastrand::Rng::with_seed(63);
+        let mut deck = new_deck();
+
+        for _ in 0..2000 {
+            // deal unevenly too, as house rules might
+            shuffle_deck_seeded(&mut deck, rng.u64(..));
+            let mut game = Game::deal_split(&deck, rng.usize(1..DECK_SIZE));
+
+            let stats = game.play();
+            assert!(stats.turns <= TURN_LIMIT);
+            assert_eq!(
+                game.p1_cards().len() + game.p2_cards().len() + game.middle_len(),
+                DECK_SIZE
+            );
+            assert!(game.validate().is_ok());
+
+            match stats.terminated {
+                Termination::Win(player) => {
+                    assert_eq!(game.winner(), Winner::from(player));
+                    assert!(game.is_over());
+                    assert_eq!(game.to_move(), player.other());
+                }
+                Termination::LimitReached => {
+                    assert_eq!(stats.turns, TURN_LIMIT);
+                    assert_eq!(game.winner(), Winner::Infinite);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn stats_summaries() {
+        let stats = Game::from_string(WORLD_RECORD_DECK).play();
+        assert_eq!(stats.to_string(), "8344 turns / 1164 tricks");
+
+        let stats = Game::from_string("K--/---").play();
+        assert_eq!(
+            format!("{stats:#}"),
+            "turns: 4\ntricks: 1\npickups: 0 (p1: 0, p2: 0)\nmax pile: 3\nlongest penalty chain: 1\ncards moved: 3\nwinner: P1"
+        );
+    }
+
+    #[test]
+    fn mid_game_notation() {
+        let mut game = Game::from_string("AK-/-QJ-");
+        for _ in 0..4 {
+            game.step();
+        }
+        assert_eq!(format!("{game:?}"), "-/J-/A-QK+3@2");
+
+        let parsed = Game::from_string("-/J-/A-QK+3@2");
+        assert_eq!(parsed, game);
+        assert_eq!(parsed.penalty(), 3);
+        assert_eq!(parsed.to_move(), Player::P2);
+        assert_eq!(
+            parsed.pile_cards(),
+            [Card::Ace, Card::Other, Card::Queen, Card::King]
+        );
+
+        // the old `p1/p2+n` form and an empty middle section still parse
+        assert_eq!(Game::from_string("-/--+3"), Game::from_string("-/--/+3"));
+        assert_eq!(format!("{:?}", Game::from_string("A-/-/")), "A-/-");
+
+        loop {
+            let notation = format!("{game:?}");
+            assert_eq!(Game::from_string(&notation), game, "{notation}");
+
+            if game.step().is_none() {
+                break;
+            }
+        }
+
+        assert_eq!(
+            Game::try_from_string(&format!("{}/{}/-", "-".repeat(26), "-".repeat(26))),
+            Err(ParseError::TooManyCards(53))
+        );
+        assert_eq!(
+            Game::try_from_string("A/-/-/-"),
+            Err(ParseError::WrongSectionCount(4))
+        );
+        assert_eq!(
+            Game::try_from_string("A/-+5"),
+            Err(ParseError::InvalidChar {
+                found: '+',
+                offset: 3
+            })
+        );
+    }
+
+    #[test]
+    fn cards_moved() {
+        // J and - are played, p1 collects them both, then plays one more card
+        let stats = Game::from_string("J-/--").play();
+        assert_eq!(stats.pickups, 1);
+        assert_eq!(stats.cards_moved, 5);
+
+        let mut game = Game::from_string(WORLD_RECORD_DECK);
+        let stats = game.clone().play();
+        assert_eq!(stats.cards_moved, game.play_observed(|_, _| {}).cards_moved);
+        assert!(stats.cards_moved > stats.turns);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn transcripts() {
+        let (stats, transcript) = Game::from_string("J-/--").play_transcript();
+        assert_eq!(transcript, [Card::Jack, Card::Other, Card::Other]);
+        assert_eq!(transcript.len(), stats.turns - 1);
+
+        let mut record = Game::from_string(WORLD_RECORD_DECK);
+        let (stats, transcript) = record.clone().play_transcript();
+        assert_eq!(transcript.len(), 8343);
+        assert_eq!(stats.turns, 8344);
+
+        // reusing a buffer gives the same transcript
+        let mut buffer = vec![Card::Ace; 3];
+        assert_eq!(record.play_transcript_into(&mut buffer), stats);
+        assert_eq!(buffer, transcript);
+    }
+
+    #[test]
+    fn turn_iterator() {
+        let mut game = Game::from_string("J-/--");
+        let players: Vec<_> = game.turns().map(|turn| turn.player).collect();
+        assert_eq!(players, [Player::P1, Player::P2, Player::P1]);
+        assert_eq!(game.winner(), Winner::P1);
+
+        let mut record = Game::from_string(WORLD_RECORD_DECK);
+        assert_eq!(record.turns().count(), 8343);
+
+        let mut infinite =
+            Game::from_string("---K---Q-KQAJ-----AAJ--J--/----------Q----KQ-J-----KA");
+        assert_eq!(infinite.turns().take(1000).count(), 1000);
+    }
+
+    #[test]
+    fn pickup_orders() {
+        assert_eq!(Rules::default().pickup, PickupOrder::Forward);
+
+        let reversed = Rules {
+            pickup: PickupOrder::Reversed,
+            ..Rules::default()
+        };
+
+        // p1 collects `J-` behind their Other, so flipping it plays the Jack a card later
+        let forward = Game::from_string("J-/-JA").play();
+        assert_eq!(
+            (forward.turns, forward.terminated),
+            (6, Termination::Win(Player::P1))
+        );
+        let stats = Game::from_string("J-/-JA").play_with_rules(&reversed);
+        assert_eq!(
+            (stats.turns, stats.terminated),
+            (7, Termination::Win(Player::P2))
+        );
+
+        let record = Game::from_string(WORLD_RECORD_DECK);
+        assert_ne!(
+            record.clone().play_with_rules(&reversed),
+            WORLD_RECORD_STATS
+        );
+    }
+
+    #[test]
+    fn extreme_rules_stay_in_bounds() {
+        // huge penalties let the middle pile grow to nearly the whole deck,
+        // which the debug assertions on every push would catch if it ever overflowed
+        let rules = Rules {
+            ace: u8::MAX,
+            king: u8::MAX,
+            queen: u8::MAX,
+            jack: u8::MAX,
+            pickup: PickupOrder::Reversed,
+        };
+
+        let mut deck = new_deck();
+        for seed in 0..200 {
+            let mut game = Game::random_seeded(&mut deck, seed);
+            let stats = game.play_with_rules(&rules);
+
+            assert!(stats.max_pile < DECK_SIZE);
+            assert_eq!(
+                game.p1_cards().len() + game.p2_cards().len() + game.middle_len(),
+                DECK_SIZE
+            );
+        }
     }
 }
@@ -1,9 +1,16 @@
-use beggar_my_neighbour::{new_deck, Card, Game, DECK_SIZE};
-use clap::{Parser, Subcommand};
+use beggar_my_neighbour::{
+    climb, new_deck, parse_decks, run_batch, DeckTemplate, Game, GameResult, GameStats,
+    LongestRecord, LongestSearch, Search, Termination, Winner, WORLD_RECORD_DECK,
+};
+use clap::{Parser, Subcommand, ValueEnum};
 use indoc::printdoc;
 use std::{
     fmt::Debug,
-    sync::atomic::{AtomicUsize, Ordering},
+    fs::File,
+    io::BufReader,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
 };
 
 /// A CLI to play games of beggar my neighbour
@@ -14,26 +21,136 @@ struct Args {
     /// Provide a deck to use instead of a random one
     #[command(subcommand)]
     command: Commands,
+    /// How to print the result of a single game
+    #[arg(long, global = true, value_enum, default_value_t = Format::Text)]
+    format: Format,
+}
+
+/// The output format for a single game
+#[derive(ValueEnum, Copy, Clone, Debug)]
+enum Format {
+    /// Human readable text
+    Text,
+    /// A single JSON object
+    Json,
 }
 
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Plays a random deck
-    Random,
+    Random {
+        /// Seed the shuffle to replay a specific random deck
+        #[arg(short, long)]
+        seed: Option<u64>,
+    },
     /// Plays a specific deck
     Deck {
-        /// The deck to use
+        /// The deck to use, or `-` to read it from standard input
         deck: String,
+        /// Print every card played and the board after it
+        #[arg(long, conflicts_with = "trace_tricks")]
+        trace: bool,
+        /// Print the board only after each time the middle pile is collected
+        #[arg(long)]
+        trace_tricks: bool,
     },
     /// Prints the stats for the longest game
     Record,
+    /// Plays a deck and checks it lasts as long as claimed, exiting with an error if it doesn't
+    Verify {
+        /// The deck to check, or `-` to read it from standard input
+        deck: String,
+        /// The claimed number of turns
+        turns: usize,
+        /// The claimed number of tricks
+        tricks: usize,
+    },
+    /// Plays every deck in a file, one per line, and prints a table of the results
+    Batch {
+        /// The file to read decks from
+        path: PathBuf,
+    },
+    /// Plays random games and prints statistics about them
+    Stats {
+        /// How many games to play
+        games: usize,
+    },
+    /// Plays a deck one card at a time, redrawing the board after every card
+    Watch {
+        /// The deck to use, or `-` to read it from standard input
+        deck: String,
+        /// How long to wait between cards, in milliseconds
+        #[arg(long, default_value_t = 100)]
+        ms: u64,
+    },
     /// Attempts to find a long game
     Longest {
         /// How many games to play
         /// Don't specify if you want to play forever
         #[arg(short, long)]
         games: Option<usize>,
+        /// Which statistic to find a record for
+        #[arg(short, long, value_enum, default_value_t = Metric::Turns)]
+        metric: Metric,
+    },
+    /// Hill climbs towards a long game by swapping pairs of cards, keeping each swap that makes the game longer
+    Climb {
+        /// The deck to start from, or `-` to read it from standard input. A random deck is used if this isn't given
+        #[arg(short, long)]
+        from: Option<String>,
+        /// How many swaps to try
+        iters: usize,
     },
+    /// Plays every deck that fits a template and prints the best one
+    Search {
+        /// The deck to search around, with `?` marking the positions to fill (e.g. `A???/????`)
+        template: String,
+        /// Which statistic to find a record for
+        #[arg(short, long, value_enum, default_value_t = Metric::Turns)]
+        metric: Metric,
+    },
+}
+
+/// A statistic of a game to search for records in
+#[derive(ValueEnum, Copy, Clone, Debug)]
+enum Metric {
+    Turns,
+    Tricks,
+    MaxPile,
+}
+
+impl From<Metric> for beggar_my_neighbour::Metric {
+    fn from(metric: Metric) -> Self {
+        match metric {
+            Metric::Turns => Self::Turns,
+            Metric::Tricks => Self::Tricks,
+            Metric::MaxPile => Self::MaxPile,
+        }
+    }
+}
+
+/// Parses the deck given on the command line, reading it from standard input if it's `-`.
+/// Exits with an error instead of panicking if it isn't a valid deck, so it can be used in a pipeline.
+fn read_deck(deck: &str) -> Game {
+    let mut line = String::new();
+    let deck = if deck == "-" {
+        if let Err(error) = std::io::stdin().read_line(&mut line) {
+            eprintln!("couldn't read a deck from standard input: {error}");
+            std::process::exit(1);
+        }
+
+        line.trim_end_matches(['\r', '\n'])
+    } else {
+        deck
+    };
+
+    match Game::try_from_string(deck) {
+        Ok(game) => game,
+        Err(error) => {
+            eprintln!("invalid deck: {error}");
+            std::process::exit(1);
+        }
+    }
 }
 
 fn game_header(game: &Game) -> String {
@@ -48,38 +165,183 @@ fn game_header(game: &Game) -> String {
 fn detail(game: &mut Game) -> String {
     let mut s = String::new();
 
-    let stats = game.play();
+    let GameResult { winner, stats } = game.play_result();
 
     s.push('\n');
 
-    s.push_str(&format!("winner: {winner:?}\n", winner = game.winner()));
+    s.push_str(&format!("winner: {winner:?}\n"));
     s.push_str(&format!("turns: {turns}\n", turns = stats.turns));
     s.push_str(&format!("tricks: {tricks}\n", tricks = stats.tricks));
+    s.push_str(&format!(
+        "max pile: {max_pile}\n",
+        max_pile = stats.max_pile
+    ));
 
     s
 }
 
-fn random_game(best_length: &AtomicUsize, deck: &mut [Card; DECK_SIZE]) {
-    let game = Game::random(deck);
-    let mut playable_game = game.clone();
-    let stats = playable_game.play();
-
-    let length = best_length.load(Ordering::Relaxed);
-
-    if stats.turns > length {
-        best_length.store(stats.turns, Ordering::Relaxed);
-        printdoc!(
-            "{header}
-
-            winner: {winner:?}
-            turns: {turns}
-            tricks: {tricks}
-            -------------------
-            ",
-            winner = playable_game.winner(),
-            turns = stats.turns,
-            tricks = stats.tricks,
-            header = game_header(&game),
+fn json(game: &mut Game) -> String {
+    let deck = format!("{game:?}");
+    let GameResult { winner, stats } = game.play_result();
+
+    format!(
+        r#"{{"deck":"{deck}","winner":"{winner:?}","turns":{turns},"tricks":{tricks}}}"#,
+        turns = stats.turns,
+        tricks = stats.tricks,
+    )
+}
+
+fn print_game(game: &mut Game, format: Format) {
+    match format {
+        Format::Text => {
+            println!("{}", game_header(game));
+            println!("{}", detail(game));
+        }
+        Format::Json => println!("{}", json(game)),
+    }
+}
+
+fn print_record(record: &LongestRecord) {
+    printdoc!(
+        "{header}seed: {seed}
+
+        winner: {winner:?}
+        turns: {turns}
+        tricks: {tricks}
+        max pile: {max_pile}
+        -------------------
+        ",
+        seed = record.seed,
+        winner = Winner::from(record.stats.terminated),
+        turns = record.stats.turns,
+        tricks = record.stats.tricks,
+        max_pile = record.stats.max_pile,
+        header = game_header(&record.game),
+    );
+}
+
+/// Plays out a copy of `game`, printing every card played and the board after it,
+/// or if `tricks_only`, only what happens when the middle pile is collected.
+fn print_trace(game: &Game, tricks_only: bool) {
+    println!("{game:#}\n");
+
+    let mut turn = 0;
+    let mut pile = game.middle_len();
+
+    game.clone().play_observed(|game, played| {
+        turn += 1;
+
+        if played.collected {
+            println!(
+                "turn {turn}: {player:?} plays {card}, so {collector:?} collects {cards} cards",
+                player = played.player,
+                card = played.card,
+                collector = played.player.other(),
+                cards = pile + 1,
+            );
+        } else if tricks_only {
+            pile = played.pile;
+            return;
+        } else {
+            println!(
+                "turn {turn}: {player:?} plays {card}",
+                player = played.player,
+                card = played.card,
+            );
+        }
+
+        println!("{game:#}\n");
+        pile = played.pile;
+    });
+}
+
+/// How often `longest` reports its progress
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Set by Ctrl-C once `longest` should stop
+static STOP: AtomicBool = AtomicBool::new(false);
+
+/// Makes the first Ctrl-C set [`STOP`] instead of killing the process, so the search can finish
+/// cleanly. A second Ctrl-C kills the process as usual.
+///
+/// This binds the C library's `signal` directly rather than pulling in a crate for it, which relies on
+/// two things every Unix-like target shares: `SIGINT` is 2 (fixed by POSIX), and `sighandler_t` is a
+/// plain `void (*)(int)`, with the null pointer being `SIG_DFL`.
+#[cfg(unix)]
+fn stop_on_interrupt() {
+    use std::ffi::c_int;
+
+    const SIGINT: c_int = 2;
+
+    /// `sighandler_t`, where `None` is the null pointer `SIG_DFL`
+    type SigHandler = Option<extern "C" fn(c_int)>;
+    const SIG_DFL: SigHandler = None;
+
+    extern "C" {
+        fn signal(signum: c_int, handler: SigHandler) -> SigHandler;
+    }
+
+    extern "C" fn handle_interrupt(_: c_int) {
+        STOP.store(true, Ordering::Relaxed);
+        // Safety: `signal` is async-signal-safe
+        unsafe { signal(SIGINT, SIG_DFL) };
+    }
+
+    // Safety: the handler only touches an atomic and calls `signal`, which are both async-signal-safe
+    unsafe { signal(SIGINT, Some(handle_interrupt)) };
+}
+
+/// Ctrl-C isn't caught on other platforms, so it still kills the search straight away
+#[cfg(not(unix))]
+fn stop_on_interrupt() {}
+
+/// How many ranges of game lengths to split the histogram into
+const HISTOGRAM_BUCKETS: usize = 10;
+/// The width of the longest histogram bar
+const HISTOGRAM_WIDTH: usize = 50;
+
+fn print_batch_stats(batch: &[GameStats]) {
+    if batch.is_empty() {
+        println!("no games played");
+        return;
+    }
+
+    let mut turns: Vec<_> = batch.iter().map(|stats| stats.turns).collect();
+    turns.sort_unstable();
+
+    let (min, max) = (turns[0], turns[turns.len() - 1]);
+    let mean_turns = turns.iter().sum::<usize>() as f64 / turns.len() as f64;
+    let mean_tricks =
+        batch.iter().map(|stats| stats.tricks).sum::<usize>() as f64 / batch.len() as f64;
+    let limit_reached = batch
+        .iter()
+        .filter(|stats| stats.terminated == Termination::LimitReached)
+        .count();
+
+    printdoc!(
+        "games: {games}
+        turns: mean {mean_turns:.1}, median {median}, min {min}, max {max}
+        tricks: mean {mean_tricks:.1}
+        turn limit reached: {limit_reached}
+
+        ",
+        games = batch.len(),
+        median = turns[turns.len() / 2],
+    );
+
+    let width = (max - min) / HISTOGRAM_BUCKETS + 1;
+    let mut buckets = [0usize; HISTOGRAM_BUCKETS];
+    for turns in &turns {
+        buckets[(turns - min) / width] += 1;
+    }
+
+    let tallest = buckets.iter().copied().max().unwrap_or(1);
+    for (i, count) in buckets.iter().enumerate() {
+        let start = min + i * width;
+        println!(
+            "{start:>6}..{end:<6} {count:>8} {bar}",
+            end = start + width,
+            bar = "#".repeat(count * HISTOGRAM_WIDTH / tallest),
         );
     }
 }
@@ -87,49 +349,184 @@ fn random_game(best_length: &AtomicUsize, deck: &mut [Card; DECK_SIZE]) {
 fn main() {
     let args = Args::parse();
     match args.command {
-        Commands::Random => {
-            let mut game = Game::random(&mut new_deck());
-            println!("{}", game_header(&game));
-            println!("{}", detail(&mut game));
+        Commands::Random { seed } => {
+            let mut game = match seed {
+                Some(seed) => Game::random_seeded(&mut new_deck(), seed),
+                None => Game::random(&mut new_deck()),
+            };
+            print_game(&mut game, args.format);
         }
-        Commands::Deck { deck } => {
-            let mut game = Game::from_string(&deck);
-            println!("{}", game_header(&game));
-            println!("{}", detail(&mut game));
+        Commands::Deck {
+            deck,
+            trace,
+            trace_tricks,
+        } => {
+            let mut game = read_deck(&deck);
+
+            if trace || trace_tricks {
+                print_trace(&game, trace_tricks);
+            }
+            print_game(&mut game, args.format);
         }
         Commands::Record => {
-            let game: &mut Game =
-                &mut Game::from_string("---AJ--Q---------QAKQJJ-QK/-----A----KJ-K--------A---");
-            println!("{}", game_header(game));
-            println!("{}", detail(game));
+            let game: &mut Game = &mut Game::from_string(WORLD_RECORD_DECK);
+            print_game(game, args.format);
+        }
+        Commands::Verify {
+            deck,
+            turns,
+            tricks,
+        } => {
+            let stats = read_deck(&deck).play();
+            let mut matches = true;
+
+            for (name, claimed, found) in [
+                ("turns", turns, stats.turns),
+                ("tricks", tricks, stats.tricks),
+            ] {
+                if claimed == found {
+                    println!("{name}: {found}");
+                } else {
+                    matches = false;
+                    println!(
+                        "{name}: {found}, but {claimed} were claimed ({difference:+})",
+                        difference = found as i64 - claimed as i64
+                    );
+                }
+            }
+
+            if !matches {
+                std::process::exit(1);
+            }
+        }
+        Commands::Batch { path } => {
+            let file = match File::open(&path) {
+                Ok(file) => file,
+                Err(error) => {
+                    eprintln!("couldn't open {}: {error}", path.display());
+                    std::process::exit(1);
+                }
+            };
+
+            println!(
+                "{:>6} {:>8} {:>8} {:>8}  deck",
+                "line", "turns", "tricks", "winner"
+            );
+            for (line, deck) in parse_decks(BufReader::new(file)).enumerate() {
+                let line = line + 1;
+
+                let deck = match deck {
+                    Ok(deck) => deck,
+                    Err(error) => {
+                        eprintln!("couldn't read line {line} of {}: {error}", path.display());
+                        std::process::exit(1);
+                    }
+                };
+
+                match deck {
+                    Ok(game) => {
+                        let stats = game.clone().play();
+                        println!(
+                            "{line:>6} {turns:>8} {tricks:>8} {winner:>8}  {game:?}",
+                            turns = stats.turns,
+                            tricks = stats.tricks,
+                            winner = format!("{:?}", Winner::from(stats.terminated)),
+                        );
+                    }
+                    Err(error) => println!("{line:>6} invalid deck: {error}"),
+                }
+            }
+        }
+        Commands::Stats { games } => print_batch_stats(&run_batch(games)),
+        Commands::Watch { deck, ms } => {
+            let mut game = read_deck(&deck);
+            let mut turns = 0;
+
+            loop {
+                // clear the screen and move the cursor back to the top left
+                print!("\x1b[2J\x1b[H");
+                println!("{game:#}\n\ncards played: {turns}");
+
+                if game.step().is_none() {
+                    break;
+                }
+                turns += 1;
+
+                std::thread::sleep(Duration::from_millis(ms));
+            }
+
+            println!("winner: {winner:?}", winner = game.winner());
+        }
+        Commands::Longest {
+            games: total_games,
+            metric,
+        } => {
+            stop_on_interrupt();
+
+            let mut search = LongestSearch::new()
+                .metric(metric.into())
+                .on_record(print_record);
+            if let Some(total_games) = total_games {
+                search = search.max_games(total_games);
+            }
+
+            let search = search.start();
+            let metric = beggar_my_neighbour::Metric::from(metric);
+            let (mut last_report, mut last_games) = (Instant::now(), 0);
+
+            while !search.is_finished() {
+                if STOP.load(Ordering::Relaxed) {
+                    search.stop();
+                }
+
+                // report the throughput on stderr, so stdout only has the records
+                if last_report.elapsed() >= PROGRESS_INTERVAL {
+                    let games = search.games();
+                    let rate = (games - last_games) as f64 / last_report.elapsed().as_secs_f64();
+                    let best = search.best().map_or(0, |record| metric.of(&record.stats));
+
+                    eprintln!("{rate:.0} games/s, {games} games played, best: {best} {metric:?}");
+                    (last_report, last_games) = (Instant::now(), games);
+                }
+
+                std::thread::sleep(Duration::from_millis(50));
+            }
+
+            println!("played {} games", search.games());
+            if let Some(record) = search.join() {
+                println!("best game:");
+                print_record(&record);
+            }
         }
-        Commands::Longest { games: total_games } => {
-            static BEST_LENGTH: AtomicUsize = AtomicUsize::new(0);
-            static GAMES: AtomicUsize = AtomicUsize::new(0);
-
-            let threads = std::thread::available_parallelism().unwrap();
-
-            let mut handles: Vec<_> = (0..threads.into())
-                .map(|_| {
-                    std::thread::spawn(move || {
-                        let mut deck = new_deck();
-                        loop {
-                            random_game(&BEST_LENGTH, &mut deck);
-                            let games = GAMES.fetch_add(1, Ordering::Relaxed);
-
-                            if let Some(total_games) = total_games {
-                                if games >= total_games {
-                                    std::process::exit(0);
-                                }
-                            }
-                        }
-                    })
-                })
-                .collect();
-
-            for handle in handles.drain(..) {
-                handle.join().unwrap();
+        Commands::Climb { from, iters } => {
+            let start = match from {
+                Some(deck) => read_deck(&deck),
+                None => Game::random(&mut new_deck()),
+            };
+
+            if start.validate().is_err() || !start.pile_cards().is_empty() {
+                eprintln!("can only climb from a fresh deal of one deck");
+                std::process::exit(1);
             }
+
+            println!("starting from {} turns", start.clone().play().turns);
+
+            let mut climbed = climb(start, iters);
+            print_game(&mut climbed, args.format);
+        }
+        Commands::Search { template, metric } => {
+            let template = match DeckTemplate::new(&template) {
+                Ok(template) => template,
+                Err(error) => {
+                    eprintln!("invalid template: {error}");
+                    std::process::exit(1);
+                }
+            };
+
+            println!("searching {} decks", template.fillings());
+
+            let mut result = Search::new(template, metric.into()).run();
+            print_game(&mut result.game, args.format);
         }
     }
 }
@@ -0,0 +1,207 @@
+#[cfg(feature = "std")]
+use crate::{new_deck, randomize_deck};
+use crate::{Card, CursorSlice, Hand, DECK_SIZE, TURN_LIMIT};
+use alloc::vec::Vec;
+
+/// The result of playing a [`MultiGame`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MultiStats {
+    /// How many cards were played
+    pub turns: usize,
+    /// How many penalties were started
+    pub tricks: usize,
+    /// The index of the winning player, or `None` if the turn limit was reached
+    pub winner: Option<usize>,
+}
+
+/// A game of beggar my neighbour between any number of players, passing to the left.
+///
+/// A player is out once they have no cards, and whoever ends up with every card wins.
+/// Unlike [`crate::Game`], the game isn't cut short when the player to move is down to
+/// their last card, so the two-player game here plays slightly longer.
+#[derive(Debug, Clone)]
+pub struct MultiGame {
+    players: Vec<Hand>,
+    middle: CursorSlice<Card, DECK_SIZE>,
+    penalty: u8,
+    /// The player who plays the next card
+    to_move: usize,
+    /// The player who played the penalty card being paid, who collects the pile if it isn't paid
+    penalty_player: usize,
+}
+
+impl MultiGame {
+    /// Deals a shuffled deck one card at a time around `players` players, so the first
+    /// `52 % players` players get an extra card.
+    ///
+    /// Panics unless there are between 2 and 52 players.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn random_n(players: usize) -> Self {
+        assert!(
+            (2..=DECK_SIZE).contains(&players),
+            "there must be between 2 and {DECK_SIZE} players"
+        );
+
+        let mut deck = new_deck();
+        randomize_deck(&mut deck, &mut fastrand::Rng::new());
+
+        let hands: Vec<Vec<Card>> = (0..players)
+            .map(|player| deck.iter().copied().skip(player).step_by(players).collect())
+            .collect();
+        let hands: Vec<&[Card]> = hands.iter().map(Vec::as_slice).collect();
+
+        Self::from_hands(&hands)
+    }
+
+    /// Starts a game with the given hands, each ordered from the first card played to the last.
+    ///
+    /// Panics if there are fewer than 2 hands or more than [`DECK_SIZE`] cards.
+    #[must_use]
+    pub fn from_hands(hands: &[&[Card]]) -> Self {
+        assert!(hands.len() >= 2, "there must be at least 2 players");
+        assert!(
+            hands.iter().map(|hand| hand.len()).sum::<usize>() <= DECK_SIZE,
+            "the hands hold more than a deck"
+        );
+
+        Self {
+            players: hands
+                .iter()
+                .map(|hand| hand.iter().copied().collect())
+                .collect(),
+            middle: CursorSlice::new(),
+            penalty: 0,
+            to_move: 0,
+            penalty_player: 0,
+        }
+    }
+
+    /// How many players the game started with, including any who are out
+    pub fn players(&self) -> usize {
+        self.players.len()
+    }
+
+    /// A player's cards, from the next one they'll play to the last
+    pub fn hand(&self, player: usize) -> &[Card] {
+        self.players[player].slice()
+    }
+
+    /// The winner, once at most one player has cards left.
+    ///
+    /// If nobody has cards, the last player to lay a penalty card is about to collect the pile.
+    pub fn winner(&self) -> Option<usize> {
+        let mut holding = self
+            .players
+            .iter()
+            .enumerate()
+            .filter(|(_, hand)| hand.len() > 0);
+
+        match (holding.next(), holding.next()) {
+            (Some((player, _)), None) => Some(player),
+            (None, _) => Some(self.penalty_player),
+            _ => None,
+        }
+    }
+
+    /// The next player to the left who still has cards
+    fn next_player(&self, player: usize) -> usize {
+        let mut next = player;
+        loop {
+            next = (next + 1) % self.players.len();
+            if next == player || self.players[next].len() > 0 {
+                break next;
+            }
+        }
+    }
+
+    /// Plays out the game, giving up after [`TURN_LIMIT`] turns
+    pub fn play(&mut self) -> MultiStats {
+        self.play_with_limit(TURN_LIMIT)
+    }
+
+    /// Plays out the game, stopping as soon as `max_turns` cards have been played
+    pub fn play_with_limit(&mut self, max_turns: usize) -> MultiStats {
+        let mut turns = 0;
+        let mut tricks = 0;
+
+        let winner = loop {
+            if let Some(winner) = self.winner() {
+                break Some(winner);
+            }
+
+            if turns >= max_turns {
+                break None;
+            }
+
+            // A player who runs out while paying a penalty is out, and the next player pays instead
+            let Some(card) = self.players[self.to_move].try_pop() else {
+                self.to_move = self.next_player(self.to_move);
+                continue;
+            };
+
+            // Safety: a game never holds more than a deck, so neither the middle pile
+            // nor the collecting player's hand can overflow
+            unsafe { self.middle.push_unchecked(card) };
+            turns += 1;
+
+            if card == Card::Other {
+                match self.penalty {
+                    0 => self.to_move = self.next_player(self.to_move),
+                    1 => {
+                        let collector = &mut self.players[self.penalty_player];
+                        unsafe { collector.push_slice(self.middle.slice()) };
+                        self.middle.clear();
+
+                        self.penalty = 0;
+                        self.to_move = self.penalty_player;
+                    }
+                    _ => self.penalty -= 1,
+                }
+            } else {
+                if self.penalty == 0 {
+                    tricks += 1;
+                }
+                self.penalty = card.penalty();
+                self.penalty_player = self.to_move;
+                self.to_move = self.next_player(self.to_move);
+            }
+        };
+
+        MultiStats {
+            turns,
+            tricks,
+            winner,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MultiGame;
+    use crate::Card::{King, Other};
+
+    #[test]
+    fn eliminated_while_paying() {
+        // p2 runs out paying p1's King, so p3 pays the last card and fails, and p1 collects.
+        // p3 then plays their last card, leaving p1 with everything.
+        let mut game = MultiGame::from_hands(&[&[King, Other], &[Other, Other], &[Other, Other]]);
+        let stats = game.play();
+
+        assert_eq!(stats.turns, 6);
+        assert_eq!(stats.tricks, 1);
+        assert_eq!(stats.winner, Some(0));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn random_games_keep_every_card() {
+        for players in 2..=5 {
+            let mut game = MultiGame::random_n(players);
+            game.play();
+
+            let held: usize = (0..players).map(|player| game.hand(player).len()).sum();
+            assert_eq!(held + game.middle.len(), 52);
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! A game written entirely in safe code, for when being sure there's no undefined behaviour
+//! matters more than speed (e.g. for teaching, or checking [`Game`] against).
+//!
+//! [`SafeGame`] plays by exactly the same rules as [`Game`], with the hands as `VecDeque`s
+//! and the middle pile as a `Vec`, so every access is bounds checked. Playing the world record
+//! this way takes roughly twice as long as [`Game::play`] (see `cargo bench -- safe`).
+#![forbid(unsafe_code)]
+
+use crate::{Card, Game, GameStats, ParseError, Player, Termination, Turn, TURN_LIMIT};
+use alloc::{collections::VecDeque, vec::Vec};
+
+/// A game of beggar my neighbour that never uses `unsafe`. See the [module docs](self).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafeGame {
+    p1: VecDeque<Card>,
+    p2: VecDeque<Card>,
+    /// The middle pile, from the first card played to the last
+    middle: Vec<Card>,
+    penalty: u8,
+    to_move: Player,
+}
+
+impl SafeGame {
+    /// Parses a game like [`Game::try_from_string`], but also requires it to hold exactly one deck
+    /// (see [`Game::validate`]), so no malformed game can be played.
+    pub fn from_string(string: &str) -> Result<Self, ParseError> {
+        let game = Game::try_from_string(string)?;
+        game.validate().map_err(ParseError::InvalidDeck)?;
+
+        Ok(Self::from(&game))
+    }
+
+    /// Splits any number of cards down the middle between the two players, like dealing a [`Game`],
+    /// with the first half going to player 1 (and the extra card to player 2 if there's an odd number).
+    ///
+    /// Unlike a [`Game`], this doesn't need to be one standard deck, so it can play decks from
+    /// [`new_deck_spec`](crate::new_deck_spec).
+    pub fn deal(deck: &[Card]) -> Self {
+        let (p1, p2) = deck.split_at(deck.len() / 2);
+
+        Self {
+            p1: p1.iter().copied().collect(),
+            p2: p2.iter().copied().collect(),
+            middle: Vec::new(),
+            penalty: 0,
+            to_move: Player::P1,
+        }
+    }
+
+    /// Player 1's cards, from the next one they'll play to the last
+    pub fn p1_cards(&self) -> impl Iterator<Item = Card> + '_ {
+        self.p1.iter().copied()
+    }
+
+    /// Player 2's cards, from the next one they'll play to the last
+    pub fn p2_cards(&self) -> impl Iterator<Item = Card> + '_ {
+        self.p2.iter().copied()
+    }
+
+    /// The middle pile, from the first card played to the last
+    pub fn pile_cards(&self) -> &[Card] {
+        &self.middle
+    }
+
+    /// The player who plays the next card
+    pub const fn to_move(&self) -> Player {
+        self.to_move
+    }
+
+    /// Whether the game is over, like [`Game::is_over`]
+    pub fn is_over(&self) -> bool {
+        match self.to_move {
+            Player::P1 => self.p1.len() <= 1,
+            Player::P2 => self.p2.len() <= 1,
+        }
+    }
+
+    /// Plays a single card like [`Game::step`], returning what happened, or `None` if the game is over
+    pub fn step(&mut self) -> Option<Turn> {
+        let player = self.to_move;
+        let (current_player, other_player) = match player {
+            Player::P1 => (&mut self.p1, &mut self.p2),
+            Player::P2 => (&mut self.p2, &mut self.p1),
+        };
+
+        if current_player.len() <= 1 {
+            return None;
+        }
+
+        let card = current_player.pop_front()?;
+        self.middle.push(card);
+
+        let mut collected = false;
+
+        if card == Card::Other {
+            match self.penalty {
+                0 => self.to_move = player.other(),
+                1 => {
+                    self.to_move = player.other();
+                    other_player.extend(self.middle.drain(..));
+                    self.penalty = 0;
+                    collected = true;
+                }
+                _ => self.penalty -= 1,
+            }
+        } else {
+            self.penalty = card as u8;
+            self.to_move = player.other();
+        }
+
+        Some(Turn {
+            player,
+            card,
+            penalty: card != Card::Other,
+            collected,
+            pile: self.middle.len(),
+        })
+    }
+
+    /// Plays out the game like [`Game::play`], giving up after [`TURN_LIMIT`] turns
+    pub fn play(&mut self) -> GameStats {
+        let mut stats = GameStats::start(self.middle.len());
+        let mut chain = GameStats::penalty_chain(&self.middle);
+
+        stats.terminated = loop {
+            if stats.turns >= TURN_LIMIT && !self.is_over() {
+                break Termination::LimitReached;
+            }
+
+            let penalty = self.penalty;
+            let pile = self.middle.len();
+
+            let Some(turn) = self.step() else {
+                break Termination::Win(self.to_move.other());
+            };
+
+            stats.tally(&turn, penalty, pile, &mut chain);
+        };
+
+        stats
+    }
+}
+
+impl From<&Game> for SafeGame {
+    fn from(game: &Game) -> Self {
+        Self {
+            p1: game.p1_cards().iter().copied().collect(),
+            p2: game.p2_cards().iter().copied().collect(),
+            middle: game.pile_cards().to_vec(),
+            penalty: game.penalty,
+            to_move: game.to_move,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SafeGame;
+    use crate::{
+        new_deck, new_deck_spec, DeckSpec, Game, InvalidDeck, ParseError, WORLD_RECORD_DECK,
+    };
+
+    #[test]
+    fn plays_like_game() {
+        let record = WORLD_RECORD_DECK;
+        assert_eq!(
+            SafeGame::from_string(record).unwrap().play(),
+            Game::from_string(record).play()
+        );
+
+        let mut deck = new_deck();
+        for seed in 0..100 {
+            let game = Game::random_seeded(&mut deck, seed);
+            assert_eq!(SafeGame::from(&game).play(), game.clone().play());
+        }
+    }
+
+    #[test]
+    fn plays_other_decks() {
+        let mut deck = new_deck_spec(DeckSpec::STANDARD.times(2));
+        for seed in 0..20 {
+            fastrand::Rng::with_seed(seed).shuffle(&mut deck);
+
+            let mut game = SafeGame::deal(&deck);
+            game.play();
+            assert_eq!(
+                game.p1_cards().count() + game.p2_cards().count() + game.pile_cards().len(),
+                104
+            );
+        }
+
+        let deck = new_deck();
+        assert_eq!(SafeGame::deal(&deck), SafeGame::from(&Game::deal(&deck)));
+    }
+
+    #[test]
+    fn rejects_partial_decks() {
+        assert!(matches!(
+            SafeGame::from_string("AK/QJ"),
+            Err(ParseError::InvalidDeck(InvalidDeck { .. }))
+        ));
+    }
+}
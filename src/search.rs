@@ -0,0 +1,839 @@
+use crate::deals::{arrangements, next_arrangement};
+use crate::{
+    new_deck, Card, CursorSlice, Game, GameStats, InvalidDeck, ParseError, Player, Termination,
+    DECK_SIZE,
+};
+use std::{
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread::JoinHandle,
+};
+
+/// How many fillings a search thread takes from the shared enumeration at a time
+const CHUNK: usize = 256;
+
+/// The number of threads to search with, falling back to 1 if it can't be queried
+#[must_use]
+pub fn available_threads() -> NonZeroUsize {
+    std::thread::available_parallelism().unwrap_or(NonZeroUsize::MIN)
+}
+
+/// A statistic of a game to search for records in
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Metric {
+    /// [`GameStats::turns`]
+    Turns,
+    /// [`GameStats::tricks`]
+    Tricks,
+    /// [`GameStats::max_pile`]
+    MaxPile,
+}
+
+impl Metric {
+    /// Reads this statistic out of a played game
+    #[must_use]
+    pub const fn of(self, stats: &GameStats) -> usize {
+        match self {
+            Self::Turns => stats.turns,
+            Self::Tricks => stats.tricks,
+            Self::MaxPile => stats.max_pile,
+        }
+    }
+}
+
+/// A deck with some positions pinned to a card and the rest free.
+///
+/// The free positions are filled with whatever cards of a full deck aren't pinned.
+#[derive(Debug, Clone)]
+pub struct DeckTemplate {
+    slots: [Option<Card>; DECK_SIZE],
+    p1_len: usize,
+    /// The unpinned cards, sorted by value so they start at the first filling
+    free: Vec<Card>,
+}
+
+impl DeckTemplate {
+    /// Parses a template from the `p1/p2` notation, with `?` marking a free position
+    /// (e.g. `"A???/????"` pins an Ace to the top of p1's hand).
+    ///
+    /// The template must have exactly [`DECK_SIZE`] positions, and can't pin more of a card than a deck has.
+    pub fn new(template: &str) -> Result<Self, ParseError> {
+        let mut sections = template.split('/');
+
+        let (Some(p1), Some(p2), None) = (sections.next(), sections.next(), sections.next()) else {
+            return Err(ParseError::WrongSectionCount(template.split('/').count()));
+        };
+
+        if let Some((offset, found)) = template
+            .char_indices()
+            .find(|&(_, c)| c != '/' && c != '?' && Card::from_char(c).is_none())
+        {
+            return Err(ParseError::InvalidChar { found, offset });
+        }
+
+        if p1.len() + p2.len() != DECK_SIZE {
+            return Err(ParseError::WrongDeckSize {
+                p1: p1.len(),
+                p2: p2.len(),
+            });
+        }
+
+        let mut slots = [None; DECK_SIZE];
+        let mut counts = [0; Card::ALL.len()];
+
+        for (slot, c) in slots.iter_mut().zip(p1.chars().chain(p2.chars())) {
+            *slot = Card::from_char(c);
+            if let Some(card) = *slot {
+                counts[card as usize] += 1;
+            }
+        }
+
+        let mut free = Vec::new();
+
+        // Card::ALL is ordered by descending value, so go backwards to keep `free` sorted
+        for card in Card::ALL.into_iter().rev() {
+            let found = counts[card as usize];
+            if found > card.deck_count() {
+                return Err(ParseError::InvalidDeck(InvalidDeck {
+                    card,
+                    expected: card.deck_count(),
+                    found,
+                }));
+            }
+
+            free.extend(std::iter::repeat_n(card, card.deck_count() - found));
+        }
+
+        Ok(Self {
+            slots,
+            p1_len: p1.len(),
+            free,
+        })
+    }
+
+    /// How many distinct decks fit this template
+    #[must_use]
+    pub fn fillings(&self) -> u128 {
+        let mut counts = [0; Card::ALL.len()];
+        for &card in &self.free {
+            counts[card as usize] += 1;
+        }
+
+        arrangements(&counts)
+    }
+
+    /// Builds the game with the free positions filled from `filling`, in order
+    fn fill(&self, filling: &[Card]) -> Game {
+        let mut filling = filling.iter();
+        let mut cards = self
+            .slots
+            .iter()
+            .map(|slot| slot.unwrap_or_else(|| *filling.next().unwrap()));
+
+        Game {
+            p1: cards.by_ref().take(self.p1_len).collect(),
+            p2: cards.collect(),
+            middle: CursorSlice::new(),
+            penalty: 0,
+            to_move: Player::P1,
+        }
+    }
+}
+
+/// The mean and spread of how long the games fitting a [`DeckTemplate`] last, from [`estimate_length`]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LengthEstimate {
+    /// How many games were played
+    pub samples: usize,
+    /// The mean [`GameStats::turns`]
+    pub mean: f64,
+    /// The standard deviation of [`GameStats::turns`] across the samples
+    pub std_dev: f64,
+}
+
+/// Estimates how long the games fitting `template` last, by filling its free positions randomly
+/// `samples` times and playing each game.
+///
+/// Games that hit [`crate::TURN_LIMIT`] count as lasting that long. With no samples, every field is 0.
+#[must_use]
+pub fn estimate_length(template: &DeckTemplate, samples: usize) -> LengthEstimate {
+    let mut rng = fastrand::Rng::new();
+    let mut filling = template.free.clone();
+    let (mut sum, mut sum_of_squares) = (0.0, 0.0);
+
+    for _ in 0..samples {
+        rng.shuffle(&mut filling);
+        let turns = template.fill(&filling).play().turns as f64;
+
+        sum += turns;
+        sum_of_squares += turns * turns;
+    }
+
+    if samples == 0 {
+        return LengthEstimate {
+            samples,
+            mean: 0.0,
+            std_dev: 0.0,
+        };
+    }
+
+    let mean = sum / samples as f64;
+    LengthEstimate {
+        samples,
+        mean,
+        // rounding can take the variance just below 0 when every game is the same length
+        std_dev: (sum_of_squares / samples as f64 - mean * mean)
+            .max(0.0)
+            .sqrt(),
+    }
+}
+
+/// The best game found by a [`Search`]
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    /// The game as it was dealt
+    pub game: Game,
+    pub stats: GameStats,
+    /// How many games were played
+    pub searched: u128,
+}
+
+/// An exhaustive search over every deck that fits a [`DeckTemplate`], split across threads.
+#[derive(Debug, Clone)]
+pub struct Search {
+    template: DeckTemplate,
+    metric: Metric,
+    threads: NonZeroUsize,
+}
+
+impl Search {
+    /// Searches for the best game by `metric`, using [`available_threads`] threads
+    #[must_use]
+    pub fn new(template: DeckTemplate, metric: Metric) -> Self {
+        Self {
+            template,
+            metric,
+            threads: available_threads(),
+        }
+    }
+
+    /// Sets how many threads to search with
+    #[must_use]
+    pub const fn threads(mut self, threads: NonZeroUsize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Plays every deck that fits the template, returning the one with the highest metric.
+    ///
+    /// Ties go to the deck that comes first in the enumeration, so the result doesn't
+    /// depend on the number of threads. Every template has at least one filling, so there is always a result.
+    #[must_use]
+    pub fn run(&self) -> SearchResult {
+        // The next filling to hand out and its position in the enumeration, or `None` once they've all been handed out
+        let next = Mutex::new((0u128, Some(self.template.free.clone())));
+
+        let results: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..self.threads.get())
+                .map(|_| scope.spawn(|| self.search_thread(&next)))
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+
+        let searched = results.iter().map(|&(_, _, searched)| searched).sum();
+        let (best, _) = results
+            .into_iter()
+            .filter_map(|(best, index, _)| best.map(|best| (best, index)))
+            .max_by(|(a, a_index), (b, b_index)| {
+                self.metric
+                    .of(&a.1)
+                    .cmp(&self.metric.of(&b.1))
+                    .then(b_index.cmp(a_index))
+            })
+            .unwrap();
+
+        SearchResult {
+            game: self.template.fill(&best.0),
+            stats: best.1,
+            searched,
+        }
+    }
+
+    /// Plays chunks of fillings until there are none left, returning the best one,
+    /// its position in the enumeration, and how many were played
+    #[allow(clippy::type_complexity)]
+    fn search_thread(
+        &self,
+        next: &Mutex<(u128, Option<Vec<Card>>)>,
+    ) -> (Option<(Vec<Card>, GameStats)>, u128, u128) {
+        let mut best: Option<(Vec<Card>, GameStats)> = None;
+        let mut best_index = 0;
+        let mut searched = 0;
+        let mut chunk = Vec::with_capacity(CHUNK);
+
+        loop {
+            {
+                let mut next = next.lock().unwrap();
+                let (index, filling) = &mut *next;
+
+                while chunk.len() < CHUNK {
+                    let Some(current) = filling else {
+                        break;
+                    };
+
+                    chunk.push((*index, current.clone()));
+                    *index += 1;
+
+                    if !next_arrangement(current) {
+                        *filling = None;
+                    }
+                }
+            }
+
+            if chunk.is_empty() {
+                return (best, best_index, searched);
+            }
+
+            for (index, filling) in chunk.drain(..) {
+                let stats = self.template.fill(&filling).play();
+                searched += 1;
+
+                let better = best.as_ref().is_none_or(|(_, best)| {
+                    let (value, best) = (self.metric.of(&stats), self.metric.of(best));
+                    value > best || (value == best && index < best_index)
+                });
+
+                if better {
+                    best = Some((filling, stats));
+                    best_index = index;
+                }
+            }
+        }
+    }
+}
+
+/// The best game found so far by a [`LongestSearch`]
+#[derive(Debug, Clone)]
+pub struct LongestRecord {
+    /// The game as it was dealt
+    pub game: Game,
+    /// The seed that deals this game with [`Game::random_seeded`]
+    pub seed: u64,
+    pub stats: GameStats,
+}
+
+/// Called with every new record a [`LongestSearch`] finds
+type OnRecord = Arc<dyn Fn(&LongestRecord) + Send + Sync>;
+
+/// A search for the best random game by some [`Metric`], run across threads until it's stopped.
+#[derive(Clone)]
+pub struct LongestSearch {
+    threads: NonZeroUsize,
+    max_games: Option<usize>,
+    metric: Metric,
+    on_record: Option<OnRecord>,
+    /// Where every new record is sent when streaming, stopping the search once nothing is receiving
+    stream: Option<Sender<(Game, GameStats)>>,
+}
+
+impl Default for LongestSearch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LongestSearch {
+    /// Searches for the longest game by [`Metric::Turns`] on [`available_threads`] threads, until stopped
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            threads: available_threads(),
+            max_games: None,
+            metric: Metric::Turns,
+            on_record: None,
+            stream: None,
+        }
+    }
+
+    /// Sets how many threads to search with
+    #[must_use]
+    pub const fn threads(mut self, threads: NonZeroUsize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Stops the search after exactly `max_games` games
+    #[must_use]
+    pub const fn max_games(mut self, max_games: usize) -> Self {
+        self.max_games = Some(max_games);
+        self
+    }
+
+    /// Sets the statistic to find a record for
+    #[must_use]
+    pub const fn metric(mut self, metric: Metric) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    /// Calls `on_record` with every new record, in increasing order.
+    ///
+    /// It's called from whichever search thread found the record, while holding the lock on the
+    /// best game, so it should be quick.
+    #[must_use]
+    pub fn on_record(mut self, on_record: impl Fn(&LongestRecord) + Send + Sync + 'static) -> Self {
+        self.on_record = Some(Arc::new(on_record));
+        self
+    }
+
+    /// Starts searching in the background
+    #[must_use]
+    pub fn start(self) -> LongestSearchHandle {
+        let shared = Arc::new(Shared {
+            best: AtomicUsize::new(0),
+            record: Mutex::new(None),
+            games: AtomicUsize::new(0),
+            stop: AtomicBool::new(false),
+        });
+
+        let threads = (0..self.threads.get())
+            .map(|_| {
+                let (search, shared) = (self.clone(), Arc::clone(&shared));
+                std::thread::spawn(move || search.search_thread(&shared))
+            })
+            .collect();
+
+        LongestSearchHandle { shared, threads }
+    }
+
+    /// Starts searching in the background, sending every new record (as it was dealt, in increasing order)
+    /// through the returned [`RecordStream`].
+    ///
+    /// Dropping the stream stops the search. If [`LongestSearch::max_games`] is set, the stream is
+    /// disconnected when they've all been played, so it can be iterated over until then.
+    #[must_use]
+    pub fn stream(mut self) -> RecordStream {
+        let (sender, receiver) = mpsc::channel();
+        self.stream = Some(sender);
+
+        RecordStream {
+            receiver,
+            handle: self.start(),
+        }
+    }
+
+    /// Plays random games until the search is stopped or enough games have been played
+    fn search_thread(&self, shared: &Shared) {
+        let mut rng = fastrand::Rng::new();
+        let mut deck = new_deck();
+        let mut playing = Game::deal(&deck);
+
+        while !shared.stop.load(Ordering::Relaxed) {
+            // claim a game, so exactly `max_games` are played however many threads there are
+            let claimed =
+                shared
+                    .games
+                    .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |games| {
+                        self.max_games
+                            .is_none_or(|max_games| games < max_games)
+                            .then_some(games + 1)
+                    });
+
+            if claimed.is_err() {
+                shared.stop.store(true, Ordering::Relaxed);
+                break;
+            }
+
+            let seed = rng.u64(..);
+            let game = Game::random_seeded(&mut deck, seed);
+
+            // play a copy that keeps being reused, so the dealt game can be kept as a record
+            playing.reset_to_deck(&deck);
+            let stats = playing.play();
+            let value = self.metric.of(&stats);
+
+            if value > shared.best.load(Ordering::Relaxed) {
+                let mut record = shared.record.lock().unwrap();
+
+                // check again, as another thread may have set a better record while we waited for the lock
+                if value > shared.best.load(Ordering::Relaxed) {
+                    shared.best.store(value, Ordering::Relaxed);
+
+                    let new_record = LongestRecord { game, seed, stats };
+                    if let Some(on_record) = &self.on_record {
+                        on_record(&new_record);
+                    }
+                    if let Some(stream) = &self.stream {
+                        // the receiver lives as long as the search, so this can't fail
+                        let _ = stream.send((new_record.game.clone(), stats));
+                    }
+                    *record = Some(new_record);
+                }
+            }
+        }
+    }
+}
+
+impl core::fmt::Debug for LongestSearch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("LongestSearch")
+            .field("threads", &self.threads)
+            .field("max_games", &self.max_games)
+            .field("metric", &self.metric)
+            .field("on_record", &self.on_record.is_some())
+            .field("stream", &self.stream.is_some())
+            .finish()
+    }
+}
+
+/// The state shared between a [`LongestSearch`]'s threads and its handle
+#[derive(Debug)]
+struct Shared {
+    /// The metric of the best game, so most games can be compared without taking the lock
+    best: AtomicUsize,
+    record: Mutex<Option<LongestRecord>>,
+    /// How many games have been started
+    games: AtomicUsize,
+    stop: AtomicBool,
+}
+
+/// A running [`LongestSearch`]
+#[derive(Debug)]
+pub struct LongestSearchHandle {
+    shared: Arc<Shared>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl LongestSearchHandle {
+    /// The best game found so far, if any game has been played
+    #[must_use]
+    pub fn best(&self) -> Option<LongestRecord> {
+        self.shared.record.lock().unwrap().clone()
+    }
+
+    /// How many games have been started so far
+    #[must_use]
+    pub fn games(&self) -> usize {
+        self.shared.games.load(Ordering::Relaxed)
+    }
+
+    /// Asks every thread to stop after the game it's playing
+    pub fn stop(&self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether every thread has stopped, either from [`LongestSearchHandle::stop`] or from playing enough games
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.threads.iter().all(JoinHandle::is_finished)
+    }
+
+    /// Waits for the search to stop, returning the best game found
+    pub fn join(self) -> Option<LongestRecord> {
+        for thread in self.threads {
+            thread.join().unwrap();
+        }
+
+        self.shared.record.lock().unwrap().take()
+    }
+}
+
+/// The new records of a [`LongestSearch`] started with [`LongestSearch::stream`], received through
+/// an mpsc [`Receiver`] (which this derefs to, so `recv`, `try_recv` and `iter` can be used as usual).
+///
+/// Dropping this stops the search, waiting for each thread to finish the game it's playing.
+#[derive(Debug)]
+pub struct RecordStream {
+    receiver: Receiver<(Game, GameStats)>,
+    handle: LongestSearchHandle,
+}
+
+impl RecordStream {
+    /// Every record the search finds, blocking while waiting for the next one
+    pub fn iter(&self) -> mpsc::Iter<'_, (Game, GameStats)> {
+        self.receiver.iter()
+    }
+}
+
+impl core::ops::Deref for RecordStream {
+    type Target = Receiver<(Game, GameStats)>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
+
+impl Drop for RecordStream {
+    fn drop(&mut self) {
+        self.handle.stop();
+        for thread in self.handle.threads.drain(..) {
+            thread.join().unwrap();
+        }
+    }
+}
+
+/// Searches for ever longer random games on [`available_threads`] threads, sending each new record
+/// through the stream as it's found. See [`LongestSearch::stream`].
+#[must_use]
+pub fn search_stream() -> RecordStream {
+    LongestSearch::new().stream()
+}
+
+/// Deals random games across [`available_threads`] threads until one lasts within `tolerance` turns of `target`,
+/// returning it as it was dealt, or `None` if none of `max_attempts` games did.
+#[must_use]
+pub fn search_for_length(target: usize, tolerance: usize, max_attempts: usize) -> Option<Game> {
+    let attempts = AtomicUsize::new(0);
+    // a flag rather than using up the attempts, which could wrap around if `max_attempts` is `usize::MAX`
+    let done = AtomicBool::new(false);
+    let found = Mutex::new(None);
+
+    std::thread::scope(|scope| {
+        for _ in 0..available_threads().get() {
+            scope.spawn(|| {
+                let mut deck = new_deck();
+
+                while !done.load(Ordering::Relaxed)
+                    && attempts.fetch_add(1, Ordering::Relaxed) < max_attempts
+                {
+                    let game = Game::random(&mut deck);
+                    let turns = game.clone().play().turns;
+
+                    if turns.abs_diff(target) <= tolerance {
+                        found.lock().unwrap().get_or_insert(game);
+                        // stop every thread from starting another game
+                        done.store(true, Ordering::Relaxed);
+                    }
+                }
+            });
+        }
+    });
+
+    found.into_inner().unwrap()
+}
+
+/// Hill climbs from `start` towards a longer game. Each of `iters` steps swaps two random cards
+/// (from either hand), keeping the swap if the game lasts more turns than the longest so far,
+/// and the longest game found is returned.
+///
+/// Only games that end count, as one hitting [`crate::TURN_LIMIT`] would otherwise beat every game
+/// that finishes, and the climb would get stuck on a game that may never end.
+///
+/// Swapping cards always leaves a legal deal, and this tends to find far longer games than dealing
+/// the same number of games at random, though it can get stuck on a game no single swap improves.
+///
+/// Panics unless `start` is a fresh deal of one deck (a game holding [`DECK_SIZE`] cards with nothing in the middle pile).
+#[must_use]
+pub fn climb(start: Game, iters: usize) -> Game {
+    climb_with_rng(start, iters, &mut fastrand::Rng::new())
+}
+
+fn climb_with_rng(start: Game, iters: usize, rng: &mut fastrand::Rng) -> Game {
+    let p1_len = start.p1_cards().len();
+    let deck: Result<[Card; DECK_SIZE], _> =
+        [start.p1_cards(), start.p2_cards()].concat().try_into();
+    let (Ok(mut deck), true) = (deck, start.pile_cards().is_empty()) else {
+        panic!("can only climb from a fresh deal of one deck");
+    };
+
+    let mut best = finished_turns(&start.clone().play());
+
+    for _ in 0..iters {
+        let (i, j) = (rng.usize(..DECK_SIZE), rng.usize(..DECK_SIZE));
+        if deck[i] == deck[j] {
+            continue;
+        }
+
+        deck.swap(i, j);
+
+        let turns = finished_turns(&Game::deal_split(&deck, p1_len).play());
+        if turns > best {
+            best = turns;
+        } else {
+            deck.swap(i, j);
+        }
+    }
+
+    Game::deal_split(&deck, p1_len)
+}
+
+/// How long a game lasted if someone won, or 0 if it hit the turn limit, so it's never kept by a climb
+const fn finished_turns(stats: &GameStats) -> usize {
+    match stats.terminated {
+        Termination::Win(_) => stats.turns,
+        Termination::LimitReached => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        climb, climb_with_rng, estimate_length, finished_turns, search_for_length, search_stream,
+        DeckTemplate, LongestSearch, Metric, Search,
+    };
+    use crate::{new_deck, Card, Game, InvalidDeck, ParseError, Termination, WORLD_RECORD_DECK};
+    use std::{
+        num::NonZeroUsize,
+        sync::{atomic::Ordering, Arc, Mutex},
+    };
+
+    #[test]
+    fn climbs_to_longer_games() {
+        let start = Game::random_seeded(&mut new_deck(), 42);
+        let climbed = climb_with_rng(start.clone(), 300, &mut fastrand::Rng::with_seed(1));
+
+        assert!(climbed.validate().is_ok());
+        let stats = climbed.clone().play();
+        assert!(stats.turns >= start.clone().play().turns);
+        assert!(matches!(stats.terminated, Termination::Win(_)));
+        assert_eq!(climb(start.clone(), 0), start);
+
+        // a game that hits the turn limit is never better than one that ends
+        let looping = Game::from_string("J--/-J-").play();
+        assert_eq!(looping.terminated, Termination::LimitReached);
+        assert_eq!(finished_turns(&looping), 0);
+    }
+
+    #[test]
+    #[should_panic = "fresh deal"]
+    fn only_climbs_from_fresh_deals() {
+        let _ = climb(Game::from_string("A-/-/K"), 10);
+    }
+
+    #[test]
+    fn finds_the_record_around_its_aces() {
+        // the world record, with its Aces and 4 Others left free
+        let template =
+            DeckTemplate::new("---?J--Q---------Q?KQJJ-QK/-----??---KJ-K--------????").unwrap();
+        assert_eq!(template.fillings(), 70);
+
+        for threads in [1, 4] {
+            let result = Search::new(template.clone(), Metric::Turns)
+                .threads(NonZeroUsize::new(threads).unwrap())
+                .run();
+
+            assert_eq!(result.searched, 70);
+            assert_eq!(result.stats.turns, 8344);
+            assert_eq!(format!("{:?}", result.game), WORLD_RECORD_DECK);
+        }
+    }
+
+    #[test]
+    fn template_errors() {
+        assert_eq!(
+            DeckTemplate::new("A?/?").unwrap_err(),
+            ParseError::WrongDeckSize { p1: 2, p2: 1 }
+        );
+        assert_eq!(
+            DeckTemplate::new(&format!("AAAAA{}/", "?".repeat(47))).unwrap_err(),
+            ParseError::InvalidDeck(InvalidDeck {
+                card: Card::Ace,
+                expected: 4,
+                found: 5
+            })
+        );
+    }
+
+    #[test]
+    fn finds_games_of_a_length() {
+        let game = search_for_length(300, 50, 100_000).unwrap();
+        assert!(game.clone().play().turns.abs_diff(300) <= 50);
+
+        // finding a game still stops the search when the attempts are unlimited
+        let game = search_for_length(300, 50, usize::MAX).unwrap();
+        assert!(game.clone().play().turns.abs_diff(300) <= 50);
+
+        // p1 has to play most of their hand before they can lose, so no deal lasts a single turn
+        assert!(search_for_length(1, 0, 100).is_none());
+    }
+
+    #[test]
+    fn estimates_lengths() {
+        // nothing is free, so every sample is the world record
+        let record = DeckTemplate::new(WORLD_RECORD_DECK).unwrap();
+        let estimate = estimate_length(&record, 10);
+        assert_eq!(estimate.samples, 10);
+        assert_eq!(estimate.mean, 8344.0);
+        assert_eq!(estimate.std_dev, 0.0);
+
+        let template =
+            DeckTemplate::new(&format!("{}/{}", "?".repeat(26), "?".repeat(26))).unwrap();
+        let estimate = estimate_length(&template, 1000);
+        assert!((100.0..1000.0).contains(&estimate.mean));
+        assert!(estimate.std_dev > 0.0);
+
+        assert_eq!(estimate_length(&template, 0).mean, 0.0);
+    }
+
+    #[test]
+    fn longest_search() {
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let found = Arc::clone(&records);
+
+        let search = LongestSearch::new()
+            .threads(NonZeroUsize::new(3).unwrap())
+            .max_games(500)
+            .metric(Metric::Tricks)
+            .on_record(move |record| found.lock().unwrap().push(record.stats.tricks))
+            .start();
+        let _ = search.best();
+
+        let record = search.join().unwrap();
+        let records = records.lock().unwrap();
+        assert_eq!(records.last(), Some(&record.stats.tricks));
+        assert!(records.windows(2).all(|pair| pair[0] < pair[1]));
+
+        // the record can be dealt again from its seed
+        let game = Game::random_seeded(&mut new_deck(), record.seed);
+        assert_eq!(game, record.game);
+        assert_eq!(game.clone().play(), record.stats);
+    }
+
+    #[test]
+    fn longest_search_plays_exactly_max_games() {
+        let search = LongestSearch::new()
+            .threads(NonZeroUsize::new(4).unwrap())
+            .max_games(1000)
+            .start();
+
+        while !search.is_finished() {
+            std::thread::yield_now();
+        }
+        assert_eq!(search.games(), 1000);
+        assert!(search.join().is_some());
+    }
+
+    #[test]
+    fn streams_records() {
+        let records: Vec<_> = LongestSearch::new()
+            .threads(NonZeroUsize::new(2).unwrap())
+            .max_games(500)
+            .stream()
+            .iter()
+            .collect();
+
+        assert!(!records.is_empty());
+        assert!(records
+            .windows(2)
+            .all(|pair| pair[0].1.turns < pair[1].1.turns));
+        for (mut game, stats) in records {
+            assert_eq!(game.play(), stats);
+        }
+
+        // the first game played is always a record, and dropping the stream stops the search
+        let stream = search_stream();
+        assert!(stream.recv().is_ok());
+        let shared = Arc::clone(&stream.handle.shared);
+        drop(stream);
+        assert!(shared.stop.load(Ordering::Relaxed));
+        assert_eq!(Arc::strong_count(&shared), 1);
+    }
+}
@@ -0,0 +1,506 @@
+//! Real playing cards, for showing a game with suits and ranks.
+//!
+//! The simulation still runs on the five kinds of [`Card`], so a [`FullGame`] plays
+//! exactly like the [`Game`] inside it; the full cards just follow along for display.
+
+use crate::{Card, CursorSlice, Game, GameStats, ParseError, Player, Turn, DECK_SIZE};
+use alloc::{collections::VecDeque, vec::Vec};
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Suit {
+    Spades,
+    Hearts,
+    Diamonds,
+    Clubs,
+}
+
+impl Suit {
+    pub const ALL: [Self; 4] = [Self::Spades, Self::Hearts, Self::Diamonds, Self::Clubs];
+
+    /// Reads a suit written as its symbol or its initial, like `♠` or `S`
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "♠" | "S" | "s" => Some(Self::Spades),
+            "♥" | "H" | "h" => Some(Self::Hearts),
+            "♦" | "D" | "d" => Some(Self::Diamonds),
+            "♣" | "C" | "c" => Some(Self::Clubs),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Suit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let s = match self {
+            Self::Spades => "♠",
+            Self::Hearts => "♥",
+            Self::Diamonds => "♦",
+            Self::Clubs => "♣",
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Rank {
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+    Ace,
+}
+
+impl Rank {
+    pub const ALL: [Self; 13] = [
+        Self::Two,
+        Self::Three,
+        Self::Four,
+        Self::Five,
+        Self::Six,
+        Self::Seven,
+        Self::Eight,
+        Self::Nine,
+        Self::Ten,
+        Self::Jack,
+        Self::Queen,
+        Self::King,
+        Self::Ace,
+    ];
+
+    /// Reads a rank written like it's displayed, also allowing `T` for ten
+    fn parse(s: &str) -> Option<Self> {
+        let rank = match s {
+            "2" => Self::Two,
+            "3" => Self::Three,
+            "4" => Self::Four,
+            "5" => Self::Five,
+            "6" => Self::Six,
+            "7" => Self::Seven,
+            "8" => Self::Eight,
+            "9" => Self::Nine,
+            "10" | "T" | "t" => Self::Ten,
+            "J" | "j" => Self::Jack,
+            "Q" | "q" => Self::Queen,
+            "K" | "k" => Self::King,
+            "A" | "a" => Self::Ace,
+            _ => return None,
+        };
+
+        Some(rank)
+    }
+
+    /// The kind of card the simulation treats this rank as
+    pub const fn card(self) -> Card {
+        match self {
+            Self::Ace => Card::Ace,
+            Self::King => Card::King,
+            Self::Queen => Card::Queen,
+            Self::Jack => Card::Jack,
+            _ => Card::Other,
+        }
+    }
+}
+
+impl Display for Rank {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let s = match self {
+            Self::Two => "2",
+            Self::Three => "3",
+            Self::Four => "4",
+            Self::Five => "5",
+            Self::Six => "6",
+            Self::Seven => "7",
+            Self::Eight => "8",
+            Self::Nine => "9",
+            Self::Ten => "10",
+            Self::Jack => "J",
+            Self::Queen => "Q",
+            Self::King => "K",
+            Self::Ace => "A",
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+/// A playing card with its suit, displayed like `A♠`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FullCard {
+    pub rank: Rank,
+    pub suit: Suit,
+}
+
+impl FullCard {
+    /// The kind of card the simulation treats this card as
+    pub const fn card(self) -> Card {
+        self.rank.card()
+    }
+
+    /// A full deck, sorted by suit and then rank
+    pub fn deck() -> [Self; DECK_SIZE] {
+        let mut deck = [Self {
+            rank: Rank::Two,
+            suit: Suit::Spades,
+        }; DECK_SIZE];
+
+        let cards = Suit::ALL
+            .iter()
+            .flat_map(|&suit| Rank::ALL.iter().map(move |&rank| Self { rank, suit }));
+        for (slot, card) in deck.iter_mut().zip(cards) {
+            *slot = card;
+        }
+
+        deck
+    }
+
+    /// Where this card sits in [`FullCard::deck`]
+    const fn index(self) -> usize {
+        self.suit as usize * Rank::ALL.len() + self.rank as usize
+    }
+}
+
+/// Reads a card written like it's displayed (`10♥`), or with the suit's initial instead (`10H`, `TH`)
+impl FromStr for FullCard {
+    type Err = InvalidFullCard;
+
+    fn from_str(s: &str) -> Result<Self, InvalidFullCard> {
+        let mut chars = s.chars();
+        let (Some(suit_char), Some(rank_char)) = (chars.next_back(), chars.next()) else {
+            return Err(InvalidFullCard::TooShort);
+        };
+        let (rank, suit) = s.split_at(s.len() - suit_char.len_utf8());
+
+        Ok(Self {
+            rank: Rank::parse(rank).ok_or(InvalidFullCard::Rank(rank_char))?,
+            suit: Suit::parse(suit).ok_or(InvalidFullCard::Suit(suit_char))?,
+        })
+    }
+}
+
+/// The error returned when a [`FullCard`] can't be read
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum InvalidFullCard {
+    /// There wasn't both a rank and a suit
+    TooShort,
+    /// Everything before the suit isn't a rank; this is its first character
+    Rank(char),
+    /// The last character isn't a suit
+    Suit(char),
+}
+
+impl Display for InvalidFullCard {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::TooShort => write!(f, "expected a rank followed by a suit"),
+            Self::Rank(found) => write!(f, "invalid rank starting with {found:?}"),
+            Self::Suit(found) => write!(f, "invalid suit {found:?}"),
+        }
+    }
+}
+
+impl core::error::Error for InvalidFullCard {}
+
+/// Reads every card of a deck written out in standard notation, separated by whitespace,
+/// like `A♠ 7♥ 10♦ ...` or `AS 7H TD ...`, checking it's all 52 cards with none repeated.
+pub fn parse_deck(notation: &str) -> Result<[FullCard; DECK_SIZE], NotationError> {
+    let mut deck = FullCard::deck();
+    let mut seen = [false; DECK_SIZE];
+    let mut count = 0;
+
+    for word in notation.split_whitespace() {
+        let offset = word.as_ptr() as usize - notation.as_ptr() as usize;
+        let card: FullCard = word
+            .parse()
+            .map_err(|error| NotationError::InvalidCard { offset, error })?;
+
+        if seen[card.index()] {
+            return Err(NotationError::Duplicate(card));
+        }
+        seen[card.index()] = true;
+
+        // there can't be more than a deck's worth of cards without one repeating
+        deck[count] = card;
+        count += 1;
+    }
+
+    if count != DECK_SIZE {
+        return Err(NotationError::WrongCount(count));
+    }
+
+    Ok(deck)
+}
+
+/// An error produced when reading a deck written out card by card with [`parse_deck`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NotationError {
+    /// The word at the given byte offset isn't a rank followed by a suit
+    InvalidCard {
+        offset: usize,
+        error: InvalidFullCard,
+    },
+    /// The deck had this many cards instead of a whole deck
+    WrongCount(usize),
+    /// A card appeared more than once
+    Duplicate(FullCard),
+}
+
+impl Display for NotationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidCard { offset, error } => {
+                write!(f, "invalid card at offset {offset}: {error}")
+            }
+            Self::WrongCount(count) => {
+                write!(f, "found {count} cards, expected the {DECK_SIZE} in a deck")
+            }
+            Self::Duplicate(card) => write!(f, "{card} appears more than once"),
+        }
+    }
+}
+
+impl core::error::Error for NotationError {}
+
+impl Display for FullCard {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}{}", self.rank, self.suit)
+    }
+}
+
+/// The full cards in each pile, moved in step with the game
+#[derive(Debug, Clone)]
+struct Track {
+    p1: VecDeque<FullCard>,
+    p2: VecDeque<FullCard>,
+    middle: Vec<FullCard>,
+}
+
+impl Track {
+    fn apply(&mut self, turn: &Turn) {
+        let (hand, other) = match turn.player {
+            Player::P1 => (&mut self.p1, &mut self.p2),
+            Player::P2 => (&mut self.p2, &mut self.p1),
+        };
+
+        self.middle.extend(hand.pop_front());
+
+        if turn.collected {
+            other.extend(self.middle.drain(..));
+        }
+    }
+}
+
+/// A [`Game`] that keeps track of the real cards in play, for display.
+#[derive(Debug, Clone)]
+pub struct FullGame {
+    game: Game,
+    track: Track,
+}
+
+impl FullGame {
+    /// Deals the cards down the middle, like [`Game::random`] does.
+    pub fn new(cards: &[FullCard]) -> Result<Self, ParseError> {
+        let game = Game::from_full(cards)?;
+        let (p1, p2) = cards.split_at(cards.len() / 2);
+
+        Ok(Self {
+            game,
+            track: Track {
+                p1: p1.iter().copied().collect(),
+                p2: p2.iter().copied().collect(),
+                middle: Vec::new(),
+            },
+        })
+    }
+
+    /// Deals a deck written out in standard notation (see [`parse_deck`]), keeping every card for display
+    pub fn from_standard_notation(notation: &str) -> Result<Self, NotationError> {
+        let deck = parse_deck(notation)?;
+        Ok(Self::new(&deck).expect("a whole deck always fits"))
+    }
+
+    /// The game as the simulation sees it
+    pub const fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Plays a single card, like [`Game::step`]
+    pub fn step(&mut self) -> Option<Turn> {
+        let turn = self.game.step()?;
+        self.track.apply(&turn);
+        Some(turn)
+    }
+
+    /// Plays out the game, like [`Game::play`]
+    pub fn play(&mut self) -> GameStats {
+        let track = &mut self.track;
+        self.game.play_observed(|_, turn| track.apply(turn))
+    }
+}
+
+impl Display for FullGame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        fn pile(f: &mut Formatter<'_>, cards: impl Iterator<Item = FullCard>) -> core::fmt::Result {
+            for (i, card) in cards.enumerate() {
+                if i > 0 {
+                    write!(f, " ")?;
+                }
+                write!(f, "{card}")?;
+            }
+            Ok(())
+        }
+
+        write!(f, "p1: ")?;
+        pile(f, self.track.p1.iter().copied())?;
+        write!(f, "\np2: ")?;
+        pile(f, self.track.p2.iter().copied())?;
+
+        if !self.track.middle.is_empty() {
+            write!(f, "\nmiddle: ")?;
+            pile(f, self.track.middle.iter().copied())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Game {
+    /// Deals real cards down the middle (like [`Game::random`]), keeping only what matters to the simulation.
+    ///
+    /// See [`FullGame`] to keep the suits and ranks around for display.
+    pub fn from_full(cards: &[FullCard]) -> Result<Self, ParseError> {
+        let (p1, p2) = cards.split_at(cards.len() / 2);
+
+        if cards.len() > DECK_SIZE {
+            return Err(ParseError::WrongDeckSize {
+                p1: p1.len(),
+                p2: p2.len(),
+            });
+        }
+
+        Ok(Self {
+            p1: p1.iter().map(|card| card.card()).collect(),
+            p2: p2.iter().map(|card| card.card()).collect(),
+            middle: CursorSlice::new(),
+            penalty: 0,
+            to_move: Player::P1,
+        })
+    }
+
+    /// Deals a deck written out in standard notation (see [`parse_deck`]), with every rank
+    /// below a Jack played as a [`Card::Other`].
+    ///
+    /// See [`FullGame::from_standard_notation`] to keep the suits and ranks around for display.
+    pub fn from_standard_notation(notation: &str) -> Result<Self, NotationError> {
+        let deck = parse_deck(notation)?;
+        Ok(Self::deal(&deck.map(FullCard::card)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_deck, FullCard, FullGame, InvalidFullCard, NotationError, Rank, Suit};
+    use crate::{Card, Game};
+
+    #[test]
+    fn plays_like_the_simulation() {
+        let mut deck = FullCard::deck();
+        fastrand::Rng::with_seed(7).shuffle(&mut deck);
+
+        let mut full = FullGame::new(&deck).unwrap();
+        let mut game = Game::from_full(&deck).unwrap();
+        assert_eq!(full.play(), game.play());
+
+        // every card is still accounted for, and matches the simulation's view of it
+        let shown = full.to_string();
+        assert_eq!(shown.matches(['♠', '♥', '♦', '♣']).count(), 52);
+        assert_eq!(full.track.p1.len(), full.game().p1_cards().len());
+        assert!(full.track.p2.iter().map(|card| card.card()).eq(full
+            .game()
+            .p2_cards()
+            .iter()
+            .copied()));
+    }
+
+    #[test]
+    fn standard_notation() {
+        let mut deck = FullCard::deck();
+        fastrand::Rng::with_seed(3).shuffle(&mut deck);
+
+        let symbols = deck.map(|card| card.to_string()).join(" ");
+        assert_eq!(parse_deck(&symbols), Ok(deck));
+
+        let game = Game::from_standard_notation(&symbols).unwrap();
+        assert_eq!(game, Game::from_full(&deck).unwrap());
+        assert!(game.validate().is_ok());
+
+        let full = FullGame::from_standard_notation(&symbols).unwrap();
+        assert_eq!(full.to_string(), FullGame::new(&deck).unwrap().to_string());
+
+        let letters = symbols
+            .replace('♠', "S")
+            .replace('♥', "h")
+            .replace('♦', "D")
+            .replace('♣', "c")
+            .replace("10", "T");
+        assert_eq!(parse_deck(&letters), Ok(deck));
+
+        assert_eq!(
+            "10♦".parse(),
+            Ok(FullCard {
+                rank: Rank::Ten,
+                suit: Suit::Diamonds
+            })
+        );
+        assert_eq!(
+            "7S".parse::<FullCard>().map(FullCard::card),
+            Ok(Card::Other)
+        );
+    }
+
+    #[test]
+    fn invalid_standard_notation() {
+        let sorted = FullCard::deck().map(|card| card.to_string()).join(" ");
+
+        assert_eq!(
+            parse_deck(&sorted.replacen("2♠", "1♠", 1)),
+            Err(NotationError::InvalidCard {
+                offset: 0,
+                error: InvalidFullCard::Rank('1')
+            })
+        );
+        assert_eq!(
+            parse_deck(&sorted.replacen("3♠", "3X", 1)),
+            Err(NotationError::InvalidCard {
+                offset: 5,
+                error: InvalidFullCard::Suit('X')
+            })
+        );
+        assert_eq!(
+            parse_deck(&sorted.replacen("2♠", "3♠", 1)),
+            Err(NotationError::Duplicate(FullCard {
+                rank: Rank::Three,
+                suit: Suit::Spades
+            }))
+        );
+        assert_eq!(
+            parse_deck(&sorted.replacen("2♠ ", "", 1)),
+            Err(NotationError::WrongCount(51))
+        );
+        assert_eq!(parse_deck(""), Err(NotationError::WrongCount(0)));
+
+        assert_eq!("S".parse::<FullCard>(), Err(InvalidFullCard::TooShort));
+        assert_eq!("11♥".parse::<FullCard>(), Err(InvalidFullCard::Rank('1')));
+        assert_eq!("Q?".parse::<FullCard>(), Err(InvalidFullCard::Suit('?')));
+    }
+}